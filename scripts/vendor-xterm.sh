@@ -0,0 +1,26 @@
+#!/bin/sh
+# Copies the pinned xterm.js release into static/vendor/xterm, where the pod terminal
+# loads it from instead of a CDN. `npm pack` checks each tarball against the
+# integrity hash the registry publishes for that version.
+set -eu
+
+XTERM_VERSION=5.3.0
+FIT_VERSION=0.8.0
+
+dest="$(cd "$(dirname "$0")/.." && pwd)/static/vendor/xterm"
+tmp="$(mktemp -d)"
+trap 'rm -rf "$tmp"' EXIT
+
+cd "$tmp"
+npm pack --silent "xterm@$XTERM_VERSION" "xterm-addon-fit@$FIT_VERSION" >/dev/null
+mkdir -p "$dest" xterm fit
+tar -xzf "xterm-$XTERM_VERSION.tgz" -C xterm
+tar -xzf "xterm-addon-fit-$FIT_VERSION.tgz" -C fit
+
+cp xterm/package/css/xterm.css xterm/package/lib/xterm.js "$dest/"
+cp xterm/package/LICENSE "$dest/LICENSE.xterm"
+cp fit/package/lib/xterm-addon-fit.js "$dest/"
+if [ -f fit/package/LICENSE ]; then
+    cp fit/package/LICENSE "$dest/LICENSE.xterm-addon-fit"
+fi
+echo "xterm $XTERM_VERSION and xterm-addon-fit $FIT_VERSION copied to $dest"
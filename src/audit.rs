@@ -0,0 +1,92 @@
+// src/audit.rs
+use anyhow::{Context, Result};
+use k8s_openapi::chrono::Utc;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::models::Identity;
+
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    timestamp: String,
+    user: &'a str,
+    groups: &'a [String],
+    context: &'a str,
+    action: &'a str,
+    target: &'a str,
+    outcome: &'a str,
+}
+
+/// Records sensitive actions as JSON lines, either to a file or to stdout.
+#[derive(Clone)]
+pub struct AuditLog {
+    file: Option<Arc<Mutex<File>>>,
+}
+
+impl AuditLog {
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        let file = match path {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("opening audit log {}", path.display()))?;
+                Some(Arc::new(Mutex::new(file)))
+            }
+            None => None,
+        };
+        Ok(AuditLog { file })
+    }
+
+    /// Binds the log to the caller and context of one request.
+    pub fn for_request(&self, identity: Option<Identity>, context: String) -> RequestAudit {
+        RequestAudit {
+            log: self.clone(),
+            identity,
+            context,
+        }
+    }
+
+    /// Writes one entry. `context` names the cluster the action ran against and `target`
+    /// identifies the object, e.g. `secrets/default/db:password`.
+    pub fn record(&self, identity: Option<&Identity>, context: &str, action: &str, target: &str, outcome: &str) {
+        let entry = AuditEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            user: identity.map(|i| i.username.as_str()).unwrap_or("anonymous"),
+            groups: identity.map(|i| i.groups.as_slice()).unwrap_or_default(),
+            context,
+            action,
+            target,
+            outcome,
+        };
+        let line = serde_json::to_string(&entry).unwrap_or_default();
+
+        match &self.file {
+            Some(file) => {
+                let mut file = file.lock().unwrap();
+                if let Err(e) = writeln!(file, "{}", line) {
+                    eprintln!("Failed to write audit entry {}: {}", line, e);
+                }
+            }
+            None => println!("audit: {}", line),
+        }
+    }
+}
+
+/// An `AuditLog` for one request, recording who acted and against which cluster.
+#[derive(Clone)]
+pub struct RequestAudit {
+    log: AuditLog,
+    identity: Option<Identity>,
+    context: String,
+}
+
+impl RequestAudit {
+    pub fn record(&self, action: &str, target: &str, outcome: &str) {
+        self.log.record(self.identity.as_ref(), &self.context, action, target, outcome);
+    }
+}
@@ -0,0 +1,575 @@
+// src/auth.rs
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+use crate::config::{AuthSettings, OidcSettings};
+use crate::error::ApiError;
+use crate::k8s_client::ClusterRegistry;
+use crate::models::{AuthInfo, Identity, LoginRequest};
+
+pub const SESSION_COOKIE: &str = "kube_inspector_session";
+/// Minimum time between JWKS reloads triggered by an unknown key id.
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Account {
+    hash: String,
+    groups: Vec<String>,
+}
+
+struct Session {
+    identity: Identity,
+    expires_at: Instant,
+}
+
+/// Verifies static tokens, basic credentials and OIDC tokens, and keeps the
+/// browser sessions created by `/api/login`. Authentication is disabled when
+/// no provider is configured.
+#[derive(Clone)]
+pub struct Authenticator {
+    tokens: Arc<HashMap<String, Identity>>,
+    accounts: Arc<HashMap<String, Account>>,
+    oidc: Option<Arc<OidcVerifier>>,
+    session_ttl: Duration,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+impl Authenticator {
+    pub fn new(settings: &AuthSettings) -> Result<Self> {
+        let tokens = match &settings.tokens_file {
+            Some(path) => load_tokens(path)?,
+            None => HashMap::new(),
+        };
+        let accounts = match &settings.htpasswd_file {
+            Some(path) => load_accounts(path)?,
+            None => HashMap::new(),
+        };
+        let oidc = settings.oidc.clone().map(|s| Arc::new(OidcVerifier::new(s)));
+
+        Ok(Authenticator {
+            tokens: Arc::new(tokens),
+            accounts: Arc::new(accounts),
+            oidc,
+            session_ttl: settings.session_ttl,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.methods().is_empty()
+    }
+
+    pub fn methods(&self) -> Vec<String> {
+        let mut methods = Vec::new();
+        if !self.accounts.is_empty() {
+            methods.push("basic".to_string());
+        }
+        if !self.tokens.is_empty() {
+            methods.push("token".to_string());
+        }
+        if self.oidc.is_some() {
+            methods.push("oidc".to_string());
+        }
+        methods
+    }
+
+    /// Resolves the caller from the session cookie or the `Authorization` header.
+    /// Returns `None` when authentication is disabled.
+    pub async fn authenticate(&self, cookie: Option<String>, authorization: Option<String>) -> Result<Option<Identity>, ApiError> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        if let Some(identity) = cookie.and_then(|token| self.session(&token)) {
+            return Ok(Some(identity));
+        }
+
+        let authorization = authorization.unwrap_or_default();
+        let bearer_session = authorization
+            .strip_prefix("Bearer ")
+            .and_then(|token| self.session(token.trim()));
+        if let Some(identity) = bearer_session {
+            return Ok(Some(identity));
+        }
+        self.verify_authorization(&authorization).await.map(Some)
+    }
+
+    async fn verify_authorization(&self, authorization: &str) -> Result<Identity, ApiError> {
+        if let Some(token) = authorization.strip_prefix("Bearer ") {
+            return self.verify_token(token.trim()).await;
+        }
+        if let Some(encoded) = authorization.strip_prefix("Basic ") {
+            let decoded = STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .ok_or_else(|| ApiError::unauthenticated("Malformed basic credentials"))?;
+            let (username, password) = decoded
+                .split_once(':')
+                .ok_or_else(|| ApiError::unauthenticated("Malformed basic credentials"))?;
+            return self.verify_password(username, password).await;
+        }
+        Err(ApiError::unauthenticated("Authentication required"))
+    }
+
+    /// Checks a static token, then falls back to OIDC verification.
+    pub async fn verify_token(&self, token: &str) -> Result<Identity, ApiError> {
+        if let Some(identity) = self.tokens.get(token) {
+            return Ok(identity.clone());
+        }
+        match &self.oidc {
+            Some(oidc) => oidc.verify(token).await,
+            None => Err(ApiError::unauthenticated("Invalid bearer token")),
+        }
+    }
+
+    pub async fn verify_password(&self, username: &str, password: &str) -> Result<Identity, ApiError> {
+        let invalid = || ApiError::unauthenticated("Invalid username or password");
+        let account = self.accounts.get(username).ok_or_else(invalid)?;
+        let hash = account.hash.clone();
+        let password = password.to_string();
+        // bcrypt is deliberately slow, so keep it off the async workers.
+        let valid = tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash).unwrap_or(false))
+            .await
+            .unwrap_or(false);
+        if !valid {
+            return Err(invalid());
+        }
+        Ok(Identity {
+            username: username.to_string(),
+            groups: account.groups.clone(),
+        })
+    }
+
+    pub fn start_session(&self, identity: Identity) -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(
+            token.clone(),
+            Session {
+                identity,
+                expires_at: now + self.session_ttl,
+            },
+        );
+        token
+    }
+
+    pub fn end_session(&self, token: &str) {
+        self.sessions.lock().unwrap().remove(token);
+    }
+
+    fn session(&self, token: &str) -> Option<Identity> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .get(token)
+            .filter(|session| session.expires_at > Instant::now())
+            .map(|session| session.identity.clone())
+    }
+
+    fn session_cookie(&self, token: &str, max_age: u64) -> String {
+        format!("{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}", SESSION_COOKIE, token, max_age)
+    }
+}
+
+/// Validates OIDC access or ID tokens, either locally against a JWKS or by
+/// calling the provider's introspection endpoint.
+struct OidcVerifier {
+    settings: OidcSettings,
+    http: reqwest::Client,
+    jwks: RwLock<Option<(JwkSet, Instant)>>,
+}
+
+impl OidcVerifier {
+    fn new(settings: OidcSettings) -> Self {
+        OidcVerifier {
+            settings,
+            http: reqwest::Client::new(),
+            jwks: RwLock::new(None),
+        }
+    }
+
+    async fn verify(&self, token: &str) -> Result<Identity, ApiError> {
+        let claims = match &self.settings.introspection_url {
+            Some(url) => self.introspect(url, token).await?,
+            None => self.decode(token).await?,
+        };
+        self.identity(&claims)
+    }
+
+    async fn introspect(&self, url: &str, token: &str) -> Result<Value, ApiError> {
+        let mut request = self.http.post(url).form(&[("token", token)]);
+        if let Some(client_id) = &self.settings.client_id {
+            request = request.basic_auth(client_id, self.settings.client_secret.as_ref());
+        }
+        let claims: Value = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(provider_unavailable)?
+            .json()
+            .await
+            .map_err(provider_unavailable)?;
+
+        if claims["active"] != Value::Bool(true) {
+            return Err(ApiError::unauthenticated("Token is not active"));
+        }
+        if let Some(issuer) = claims["iss"].as_str() {
+            if issuer != self.settings.issuer {
+                return Err(ApiError::unauthenticated("Token was issued by an unexpected issuer"));
+            }
+        }
+        Ok(claims)
+    }
+
+    async fn decode(&self, token: &str) -> Result<Value, ApiError> {
+        let header = decode_header(token).map_err(|e| ApiError::unauthenticated(format!("Invalid token: {}", e)))?;
+        let jwk = self.signing_key(header.kid.as_deref()).await?;
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| ApiError::unauthenticated(format!("Unusable signing key: {}", e)))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&self.settings.issuer]);
+        match &self.settings.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        decode::<Value>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| ApiError::unauthenticated(format!("Invalid token: {}", e)))
+    }
+
+    /// Finds the key for `kid`, reloading the key set once if it is unknown
+    /// (the provider may have rotated its keys).
+    async fn signing_key(&self, kid: Option<&str>) -> Result<Jwk, ApiError> {
+        let fresh = {
+            let jwks = self.jwks.read().await;
+            if let Some(jwk) = jwks.as_ref().and_then(|(set, _)| find_key(set, kid)) {
+                return Ok(jwk);
+            }
+            jwks.as_ref()
+                .is_some_and(|(_, loaded_at)| loaded_at.elapsed() < JWKS_REFRESH_INTERVAL)
+        };
+        if fresh {
+            return Err(ApiError::unauthenticated("No signing key matches the token"));
+        }
+
+        let set = self.load_jwks().await?;
+        let jwk = find_key(&set, kid);
+        *self.jwks.write().await = Some((set, Instant::now()));
+        jwk.ok_or_else(|| ApiError::unauthenticated("No signing key matches the token"))
+    }
+
+    async fn load_jwks(&self) -> Result<JwkSet, ApiError> {
+        if let Some(path) = &self.settings.jwks_file {
+            let bytes = tokio::fs::read(path)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?;
+            return serde_json::from_slice(&bytes)
+                .map_err(|e| ApiError::internal(format!("Invalid JWKS in {}: {}", path.display(), e)));
+        }
+
+        let url = match &self.settings.jwks_url {
+            Some(url) => url.clone(),
+            None => {
+                let discovery = format!("{}/.well-known/openid-configuration", self.settings.issuer.trim_end_matches('/'));
+                let document: Value = self.get_json(&discovery).await?;
+                document["jwks_uri"]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| ApiError::new(StatusCode::BAD_GATEWAY, "InvalidResponse", "Discovery document has no jwks_uri"))?
+            }
+        };
+        self.get_json(&url).await
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, ApiError> {
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(provider_unavailable)?
+            .json()
+            .await
+            .map_err(provider_unavailable)
+    }
+
+    fn identity(&self, claims: &Value) -> Result<Identity, ApiError> {
+        let username = claims[&self.settings.username_claim]
+            .as_str()
+            .ok_or_else(|| ApiError::unauthenticated(format!("Token has no {} claim", self.settings.username_claim)))?;
+        let groups = match &claims[&self.settings.groups_claim] {
+            Value::Array(values) => values.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+            Value::String(group) => vec![group.clone()],
+            _ => Vec::new(),
+        };
+        Ok(Identity {
+            username: username.to_string(),
+            groups,
+        })
+    }
+}
+
+/// Maps dashboard identities to the Kubernetes user and groups to impersonate.
+///
+/// ```json
+/// {
+///   "userPrefix": "dashboard:",
+///   "groupPrefix": "dashboard:",
+///   "users": { "alice": { "user": "alice@example.com", "groups": ["platform"] } },
+///   "groups": { "admins": ["cluster-admins"] }
+/// }
+/// ```
+///
+/// Users and groups without an entry are passed through with the prefix applied.
+/// Both prefixes default to `kube-inspector:`, so a dashboard group such as
+/// `system:masters` can't pick up a built-in Kubernetes role unless mapped explicitly.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityMapping {
+    #[serde(default = "default_prefix")]
+    user_prefix: String,
+    #[serde(default = "default_prefix")]
+    group_prefix: String,
+    #[serde(default)]
+    users: HashMap<String, UserMapping>,
+    #[serde(default)]
+    groups: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct UserMapping {
+    user: Option<String>,
+    #[serde(default)]
+    groups: Vec<String>,
+}
+
+impl Default for IdentityMapping {
+    fn default() -> Self {
+        IdentityMapping {
+            user_prefix: default_prefix(),
+            group_prefix: default_prefix(),
+            users: HashMap::new(),
+            groups: HashMap::new(),
+        }
+    }
+}
+
+fn default_prefix() -> String {
+    "kube-inspector:".to_string()
+}
+
+impl IdentityMapping {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Returns the Kubernetes identity requests should impersonate for `identity`.
+    pub fn kube_identity(&self, identity: &Identity) -> Identity {
+        let user = self.users.get(&identity.username);
+        let username = user
+            .and_then(|u| u.user.clone())
+            .unwrap_or_else(|| format!("{}{}", self.user_prefix, identity.username));
+
+        let mut groups = BTreeSet::new();
+        for group in &identity.groups {
+            match self.groups.get(group) {
+                Some(mapped) => groups.extend(mapped.iter().cloned()),
+                None => {
+                    groups.insert(format!("{}{}", self.group_prefix, group));
+                }
+            }
+        }
+        groups.extend(user.iter().flat_map(|u| u.groups.iter().cloned()));
+
+        Identity {
+            username,
+            groups: groups.into_iter().collect(),
+        }
+    }
+}
+
+fn find_key(set: &JwkSet, kid: Option<&str>) -> Option<Jwk> {
+    match kid {
+        Some(kid) => set.find(kid).cloned(),
+        None if set.keys.len() == 1 => set.keys.first().cloned(),
+        None => None,
+    }
+}
+
+fn provider_unavailable(err: reqwest::Error) -> ApiError {
+    ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "ServiceUnavailable",
+        format!("OIDC provider request failed: {}", err),
+    )
+}
+
+/// Parses a kube-apiserver style token file: `token,user,uid,"group1,group2"`.
+fn load_tokens(path: &Path) -> Result<HashMap<String, Identity>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut tokens = HashMap::new();
+    for line in contents.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut fields = line.splitn(4, ',');
+        let (Some(token), Some(user)) = (fields.next(), fields.next()) else {
+            anyhow::bail!("invalid line in {}: expected token,user,uid[,groups]", path.display());
+        };
+        let _uid = fields.next();
+        let groups = split_groups(fields.next().unwrap_or_default().trim_matches('"'));
+        tokens.insert(
+            token.trim().to_string(),
+            Identity {
+                username: user.trim().to_string(),
+                groups,
+            },
+        );
+    }
+    Ok(tokens)
+}
+
+/// Parses `user:bcrypt-hash[:group1,group2]` lines.
+fn load_accounts(path: &Path) -> Result<HashMap<String, Account>> {
+    let contents = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut accounts = HashMap::new();
+    for line in contents.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut fields = line.splitn(3, ':');
+        let (Some(user), Some(hash)) = (fields.next(), fields.next()) else {
+            anyhow::bail!("invalid line in {}: expected user:hash", path.display());
+        };
+        if !hash.starts_with("$2") {
+            println!("Skipping {} in {}: only bcrypt hashes are supported", user, path.display());
+            continue;
+        }
+        accounts.insert(
+            user.to_string(),
+            Account {
+                hash: hash.to_string(),
+                groups: split_groups(fields.next().unwrap_or_default()),
+            },
+        );
+    }
+    Ok(accounts)
+}
+
+fn split_groups(groups: &str) -> Vec<String> {
+    groups
+        .split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub async fn get_auth_info(user: Option<Identity>, auth: Authenticator, registry: ClusterRegistry) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&AuthInfo {
+        enabled: auth.is_enabled(),
+        methods: auth.methods(),
+        impersonating: registry.kube_identity(user.as_ref()),
+        user,
+    }))
+}
+
+pub async fn login(request: LoginRequest, auth: Authenticator) -> Result<impl Reply, Rejection> {
+    if !auth.is_enabled() {
+        return Err(warp::reject::custom(ApiError::bad_request("Authentication is not enabled")));
+    }
+    let identity = match request {
+        LoginRequest { username: Some(username), password: Some(password), .. } => {
+            auth.verify_password(&username, &password).await
+        }
+        LoginRequest { token: Some(token), .. } => auth.verify_token(token.trim()).await,
+        _ => Err(ApiError::bad_request("Provide a username and password, or a token")),
+    }
+    .map_err(warp::reject::custom)?;
+
+    let session = auth.start_session(identity.clone());
+    let cookie = auth.session_cookie(&session, auth.session_ttl.as_secs());
+    Ok(warp::reply::with_header(warp::reply::json(&identity), "set-cookie", cookie))
+}
+
+pub async fn logout(session: Option<String>, auth: Authenticator) -> Result<impl Reply, Rejection> {
+    if let Some(token) = session {
+        auth.end_session(&token);
+    }
+    let cookie = auth.session_cookie("", 0);
+    Ok(warp::reply::with_header(warp::reply::with_status(warp::reply(), StatusCode::NO_CONTENT), "set-cookie", cookie))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn identity(username: &str, groups: &[&str]) -> Identity {
+        Identity {
+            username: username.to_string(),
+            groups: groups.iter().map(|g| g.to_string()).collect(),
+        }
+    }
+
+    fn mapping(value: Value) -> IdentityMapping {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn maps_explicitly_listed_users_and_groups() {
+        let mapping = mapping(json!({
+            "users": { "alice": { "user": "alice@example.com", "groups": ["platform"] } },
+            "groups": { "admins": ["cluster-admins", "auditors"] }
+        }));
+        let kube = mapping.kube_identity(&identity("alice", &["admins"]));
+        assert_eq!(kube, identity("alice@example.com", &["auditors", "cluster-admins", "platform"]));
+    }
+
+    #[test]
+    fn prefixes_unmapped_users_and_groups_by_default() {
+        let expected = identity("kube-inspector:bob", &["kube-inspector:devs", "kube-inspector:system:masters"]);
+        let bob = identity("bob", &["system:masters", "devs"]);
+
+        assert_eq!(IdentityMapping::default().kube_identity(&bob), expected);
+        // A mapping file that only sets `users` keeps the default prefixes
+        let partial = mapping(json!({ "users": { "alice": { "user": "alice@example.com" } } }));
+        assert_eq!(partial.kube_identity(&bob), expected);
+    }
+
+    #[test]
+    fn mapped_user_without_a_name_keeps_the_prefixed_username() {
+        let mapping = mapping(json!({ "users": { "carol": { "groups": ["ops"] } } }));
+        let kube = mapping.kube_identity(&identity("carol", &["devs"]));
+        assert_eq!(kube, identity("kube-inspector:carol", &["kube-inspector:devs", "ops"]));
+    }
+
+    #[test]
+    fn empty_prefixes_pass_names_through() {
+        let mapping = mapping(json!({
+            "userPrefix": "",
+            "groupPrefix": "",
+            "groups": { "admins": ["cluster-admins"] }
+        }));
+        let kube = mapping.kube_identity(&identity("dave", &["admins", "devs"]));
+        assert_eq!(kube, identity("dave", &["cluster-admins", "devs"]));
+    }
+
+    #[test]
+    fn custom_prefixes_apply_separately() {
+        let mapping = mapping(json!({ "userPrefix": "oidc:", "groupPrefix": "oidc-group:" }));
+        let kube = mapping.kube_identity(&identity("erin", &["devs"]));
+        assert_eq!(kube, identity("oidc:erin", &["oidc-group:devs"]));
+    }
+}
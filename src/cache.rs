@@ -0,0 +1,398 @@
+// src/cache.rs
+use futures::{stream, Stream, StreamExt};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, Pod, Service};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::runtime::{reflector, watcher, WatchStreamExt};
+use kube::core::ApiResource;
+use kube::{Api, Client, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use warp::http::HeaderValue;
+use warp::Reply;
+
+use crate::discovery::Catalog;
+use crate::metrics::UsageCache;
+use crate::handlers::{
+    configmap_info, cron_job_info, daemon_set_info, deployment_info, job_info, namespace_info, network_policy_info,
+    node_info, pod_info, replica_set_info, service_info, stateful_set_info,
+};
+use crate::routing::ingress_info;
+
+/// How often an idle feed checks whether it still has subscribers.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// How long a feed is kept warm after the last list request read from it.
+const CACHE_TTL: Duration = Duration::from_secs(600);
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Namespaces,
+    Pods,
+    Services,
+    Deployments,
+    ReplicaSets,
+    StatefulSets,
+    DaemonSets,
+    Jobs,
+    CronJobs,
+    ConfigMaps,
+    NetworkPolicies,
+    Ingresses,
+    Nodes,
+}
+
+impl ResourceKind {
+    /// Parses the resource segment used in API paths, e.g. `pods` or `networkpolicies`.
+    pub fn from_path(segment: &str) -> Option<Self> {
+        match segment {
+            "namespaces" => Some(ResourceKind::Namespaces),
+            "pods" => Some(ResourceKind::Pods),
+            "services" => Some(ResourceKind::Services),
+            "deployments" => Some(ResourceKind::Deployments),
+            "replicasets" => Some(ResourceKind::ReplicaSets),
+            "statefulsets" => Some(ResourceKind::StatefulSets),
+            "daemonsets" => Some(ResourceKind::DaemonSets),
+            "jobs" => Some(ResourceKind::Jobs),
+            "cronjobs" => Some(ResourceKind::CronJobs),
+            "configmaps" => Some(ResourceKind::ConfigMaps),
+            "networkpolicies" => Some(ResourceKind::NetworkPolicies),
+            "ingresses" => Some(ResourceKind::Ingresses),
+            "nodes" => Some(ResourceKind::Nodes),
+            _ => None,
+        }
+    }
+
+    pub fn is_namespaced(self) -> bool {
+        !matches!(self, ResourceKind::Namespaces | ResourceKind::Nodes)
+    }
+
+    /// Type information for working with this kind as a `DynamicObject`.
+    pub fn api_resource(self) -> ApiResource {
+        match self {
+            ResourceKind::Namespaces => ApiResource::erase::<Namespace>(&()),
+            ResourceKind::Pods => ApiResource::erase::<Pod>(&()),
+            ResourceKind::Services => ApiResource::erase::<Service>(&()),
+            ResourceKind::Deployments => ApiResource::erase::<Deployment>(&()),
+            ResourceKind::ReplicaSets => ApiResource::erase::<ReplicaSet>(&()),
+            ResourceKind::StatefulSets => ApiResource::erase::<StatefulSet>(&()),
+            ResourceKind::DaemonSets => ApiResource::erase::<DaemonSet>(&()),
+            ResourceKind::Jobs => ApiResource::erase::<Job>(&()),
+            ResourceKind::CronJobs => ApiResource::erase::<CronJob>(&()),
+            ResourceKind::ConfigMaps => ApiResource::erase::<ConfigMap>(&()),
+            ResourceKind::NetworkPolicies => ApiResource::erase::<NetworkPolicy>(&()),
+            ResourceKind::Ingresses => ApiResource::erase::<Ingress>(&()),
+            ResourceKind::Nodes => ApiResource::erase::<Node>(&()),
+        }
+    }
+}
+
+/// Event pushed to browsers. `Sync` carries the full current list and replaces
+/// whatever the client had before.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "UPPERCASE")]
+pub enum WatchEvent {
+    Sync { objects: Vec<Value> },
+    Added { object: Value },
+    Modified { object: Value },
+    Deleted { object: Value },
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FeedKey {
+    kind: ResourceKind,
+    namespace: Option<String>,
+}
+
+#[derive(Clone)]
+struct Feed {
+    tx: broadcast::Sender<WatchEvent>,
+    ready: Arc<AtomicBool>,
+    healthy: Arc<AtomicBool>,
+    synced_at: Arc<Mutex<Option<DateTime<Utc>>>>,
+    last_read: Arc<Mutex<Instant>>,
+    snapshot: Arc<dyn Fn() -> Vec<Value> + Send + Sync>,
+}
+
+impl Feed {
+    fn sync_event(&self) -> Option<WatchEvent> {
+        if self.ready.load(Ordering::Acquire) {
+            Some(WatchEvent::Sync { objects: (self.snapshot)() })
+        } else {
+            None
+        }
+    }
+}
+
+/// Result of a cache lookup for list endpoints.
+pub struct CachedList {
+    pub objects: Vec<Value>,
+    pub status: CacheStatus,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CacheStatus {
+    /// Served from a synced store whose watch is healthy.
+    Hit { synced_at: Option<DateTime<Utc>> },
+    /// Served from a store whose watch is currently failing and retrying.
+    Stale { synced_at: Option<DateTime<Utc>> },
+    /// The store was not ready, so the handler listed from the API server.
+    Miss,
+}
+
+/// Shares one reflector per resource kind and namespace between list endpoints
+/// and watch subscribers, so repeated requests don't LIST against the API server.
+#[derive(Clone)]
+pub struct ResourceCache {
+    client: Client,
+    feeds: Arc<Mutex<HashMap<FeedKey, Feed>>>,
+    catalog: Catalog,
+    usage: UsageCache,
+}
+
+impl ResourceCache {
+    pub fn new(client: Client) -> Self {
+        ResourceCache {
+            client,
+            feeds: Arc::new(Mutex::new(HashMap::new())),
+            catalog: Catalog::default(),
+            usage: UsageCache::default(),
+        }
+    }
+
+    pub fn client(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// API resources discovered for this client.
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// Recent metrics.k8s.io usage read with this client.
+    pub fn usage(&self) -> &UsageCache {
+        &self.usage
+    }
+
+    /// Returns the cached list if the store has synced, otherwise starts warming
+    /// it in the background and returns `None` so the caller lists directly.
+    pub fn list(&self, kind: ResourceKind, namespace: Option<&str>) -> Option<CachedList> {
+        let key = FeedKey {
+            kind,
+            namespace: namespace.filter(|_| kind.is_namespaced()).map(str::to_string),
+        };
+        let feed = {
+            let mut feeds = self.feeds.lock().unwrap();
+            self.feed(&mut feeds, key)
+        };
+        *feed.last_read.lock().unwrap() = Instant::now();
+
+        if !feed.ready.load(Ordering::Acquire) {
+            return None;
+        }
+        let synced_at = *feed.synced_at.lock().unwrap();
+        let status = if feed.healthy.load(Ordering::Acquire) {
+            CacheStatus::Hit { synced_at }
+        } else {
+            CacheStatus::Stale { synced_at }
+        };
+        Some(CachedList {
+            objects: (feed.snapshot)(),
+            status,
+        })
+    }
+
+    /// Subscribes to a feed, starting its watcher if nobody is watching yet.
+    /// The returned stream starts with a `Sync` event once the initial list is known.
+    pub fn subscribe(&self, kind: ResourceKind, namespace: Option<String>) -> impl Stream<Item = WatchEvent> {
+        let key = FeedKey { kind, namespace };
+        let (feed, rx) = {
+            let mut feeds = self.feeds.lock().unwrap();
+            let feed = self.feed(&mut feeds, key);
+            let rx = feed.tx.subscribe();
+            (feed, rx)
+        };
+
+        // Subscribing before taking the snapshot means an event may be seen twice,
+        // but never missed; clients apply events idempotently by name.
+        let initial = feed.sync_event();
+        stream::iter(initial).chain(stream::unfold((rx, feed), |(mut rx, feed)| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, (rx, feed))),
+                    Err(broadcast::error::RecvError::Lagged(_)) => {
+                        if let Some(event) = feed.sync_event() {
+                            return Some((event, (rx, feed)));
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    fn feed(&self, feeds: &mut HashMap<FeedKey, Feed>, key: FeedKey) -> Feed {
+        match feeds.get(&key) {
+            Some(feed) => feed.clone(),
+            None => {
+                let feed = self.start_feed(key.clone());
+                feeds.insert(key, feed.clone());
+                feed
+            }
+        }
+    }
+
+    fn start_feed(&self, key: FeedKey) -> Feed {
+        let client = self.client.clone();
+        let namespace = key.namespace.clone();
+        match key.kind {
+            ResourceKind::Namespaces => self.spawn_feed(key, Api::<Namespace>::all(client), namespace_info),
+            ResourceKind::Pods => self.spawn_feed(key, scoped_api::<Pod>(client, namespace), pod_info),
+            ResourceKind::Services => self.spawn_feed(key, scoped_api::<Service>(client, namespace), service_info),
+            ResourceKind::Deployments => {
+                self.spawn_feed(key, scoped_api::<Deployment>(client, namespace), deployment_info)
+            }
+            ResourceKind::ReplicaSets => {
+                self.spawn_feed(key, scoped_api::<ReplicaSet>(client, namespace), replica_set_info)
+            }
+            ResourceKind::StatefulSets => {
+                self.spawn_feed(key, scoped_api::<StatefulSet>(client, namespace), stateful_set_info)
+            }
+            ResourceKind::DaemonSets => self.spawn_feed(key, scoped_api::<DaemonSet>(client, namespace), daemon_set_info),
+            ResourceKind::Jobs => self.spawn_feed(key, scoped_api::<Job>(client, namespace), job_info),
+            ResourceKind::CronJobs => self.spawn_feed(key, scoped_api::<CronJob>(client, namespace), cron_job_info),
+            ResourceKind::ConfigMaps => self.spawn_feed(key, scoped_api::<ConfigMap>(client, namespace), configmap_info),
+            ResourceKind::NetworkPolicies => {
+                self.spawn_feed(key, scoped_api::<NetworkPolicy>(client, namespace), network_policy_info)
+            }
+            ResourceKind::Ingresses => self.spawn_feed(key, scoped_api::<Ingress>(client, namespace), ingress_info),
+            ResourceKind::Nodes => self.spawn_feed(key, Api::<Node>::all(client), node_info),
+        }
+    }
+
+    fn spawn_feed<K, T>(&self, key: FeedKey, api: Api<K>, to_info: fn(&K) -> T) -> Feed
+    where
+        K: Resource + Clone + DeserializeOwned + Debug + Send + Sync + 'static,
+        K::DynamicType: Default + Eq + std::hash::Hash + Clone,
+        T: Serialize + 'static,
+    {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let (reader, writer) = reflector::store();
+        let ready = Arc::new(AtomicBool::new(false));
+        let healthy = Arc::new(AtomicBool::new(false));
+        let synced_at = Arc::new(Mutex::new(None));
+        let last_read = Arc::new(Mutex::new(Instant::now()));
+        let feed = Feed {
+            tx: tx.clone(),
+            ready: ready.clone(),
+            healthy: healthy.clone(),
+            synced_at: synced_at.clone(),
+            last_read: last_read.clone(),
+            snapshot: Arc::new(move || {
+                reader.state().iter().map(|obj| to_value(to_info(obj))).collect()
+            }),
+        };
+
+        let feeds = self.feeds.clone();
+        let events = reflector(writer, watcher(api, watcher::Config::default())).default_backoff();
+        tokio::spawn(async move {
+            let mut events = std::pin::pin!(events);
+            let mut known = HashSet::new();
+            let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    event = events.next() => {
+                        if let Some(Ok(_)) = &event {
+                            healthy.store(true, Ordering::Release);
+                            *synced_at.lock().unwrap() = Some(Utc::now());
+                        }
+                        match event {
+                            Some(Ok(watcher::Event::Restarted(objs))) => {
+                                known = objs.iter().map(object_key).collect();
+                                ready.store(true, Ordering::Release);
+                                let objects = objs.iter().map(|obj| to_value(to_info(obj))).collect();
+                                let _ = tx.send(WatchEvent::Sync { objects });
+                            }
+                            Some(Ok(watcher::Event::Applied(obj))) => {
+                                let object = to_value(to_info(&obj));
+                                let event = if known.insert(object_key(&obj)) {
+                                    WatchEvent::Added { object }
+                                } else {
+                                    WatchEvent::Modified { object }
+                                };
+                                let _ = tx.send(event);
+                            }
+                            Some(Ok(watcher::Event::Deleted(obj))) => {
+                                known.remove(&object_key(&obj));
+                                let _ = tx.send(WatchEvent::Deleted { object: to_value(to_info(&obj)) });
+                            }
+                            Some(Err(e)) => {
+                                eprintln!("Watch error for {:?}: {}", key, e);
+                                healthy.store(false, Ordering::Release);
+                                let _ = tx.send(WatchEvent::Error { message: e.to_string() });
+                            }
+                            None => break,
+                        }
+                    }
+                    _ = idle_check.tick() => {
+                        // Checked under the lock so a concurrent subscribe or list either
+                        // sees this feed removed or keeps it alive.
+                        let mut feeds = feeds.lock().unwrap();
+                        if tx.receiver_count() == 0 && last_read.lock().unwrap().elapsed() > CACHE_TTL {
+                            feeds.remove(&key);
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        feed
+    }
+}
+
+fn scoped_api<K>(client: Client, namespace: Option<String>) -> Api<K>
+where
+    K: Resource<Scope = k8s_openapi::NamespaceResourceScope>,
+    K::DynamicType: Default,
+{
+    match namespace {
+        Some(ns) => Api::namespaced(client, &ns),
+        None => Api::all(client),
+    }
+}
+
+fn object_key<K: Resource>(obj: &K) -> String {
+    format!("{}/{}", obj.namespace().unwrap_or_default(), obj.name_any())
+}
+
+fn to_value<T: Serialize>(info: T) -> Value {
+    serde_json::to_value(info).unwrap_or(Value::Null)
+}
+
+/// Serializes a list response and attaches cache freshness headers.
+pub fn cached_json<T: Serialize>(body: &T, status: CacheStatus) -> warp::reply::Response {
+    let (label, synced_at) = match status {
+        CacheStatus::Hit { synced_at } => ("HIT", synced_at),
+        CacheStatus::Stale { synced_at } => ("STALE", synced_at),
+        CacheStatus::Miss => ("MISS", None),
+    };
+    let mut response = warp::reply::json(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert("x-cache", HeaderValue::from_static(label));
+    if let Some(ts) = synced_at.and_then(|ts| HeaderValue::from_str(&ts.to_rfc3339()).ok()) {
+        headers.insert("x-cache-synced-at", ts);
+    }
+    response
+}
@@ -0,0 +1,179 @@
+// src/config.rs
+use std::env;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const ENV_PREFIX: &str = "KUBE_INSPECTOR_";
+
+/// Server settings read from `KUBE_INSPECTOR_*` environment variables.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub auth: AuthSettings,
+    pub impersonation: ImpersonationSettings,
+    pub secrets: SecretSettings,
+    pub drain: DrainSettings,
+    pub metrics: MetricsSettings,
+    /// File that audit entries are appended to as JSON lines; stdout when unset.
+    pub audit_log: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthSettings {
+    /// CSV file in the kube-apiserver token format: `token,user,uid,"group1,group2"`.
+    pub tokens_file: Option<PathBuf>,
+    /// htpasswd-style file of `user:bcrypt-hash[:group1,group2]` lines.
+    pub htpasswd_file: Option<PathBuf>,
+    pub oidc: Option<OidcSettings>,
+    pub session_ttl: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcSettings {
+    pub issuer: String,
+    pub audience: Option<String>,
+    /// Local JWKS document, checked before `jwks_url`. Handy for testing against a mock issuer.
+    pub jwks_file: Option<PathBuf>,
+    pub jwks_url: Option<String>,
+    /// RFC 7662 introspection endpoint. When set, tokens are introspected instead of verified locally.
+    pub introspection_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub username_claim: String,
+    pub groups_claim: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImpersonationSettings {
+    /// Send Kubernetes requests as the signed-in user instead of the server's own credentials.
+    pub enabled: bool,
+    /// JSON file mapping dashboard users and groups to Kubernetes users and groups.
+    pub mapping_file: Option<PathBuf>,
+    /// Count pods per node for the Nodes list with the server's credentials, shared
+    /// by all users, instead of listing pods as each caller.
+    pub shared_node_pod_counts: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SecretSettings {
+    /// Allow decoding individual Secret keys through the reveal endpoint.
+    pub allow_reveal: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DrainSettings {
+    /// How long a drain may wait for evictions blocked by PodDisruptionBudgets and
+    /// for evicted pods to terminate, unless the request asks for a different timeout.
+    pub timeout: Duration,
+}
+
+impl Default for DrainSettings {
+    fn default() -> Self {
+        DrainSettings {
+            timeout: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsSettings {
+    /// How often pod and node usage is sampled for the history endpoint.
+    pub sample_interval: Duration,
+    /// How much history is kept per object. Zero turns the sampler off.
+    pub retention: Duration,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        MetricsSettings {
+            sample_interval: Duration::from_secs(30),
+            retention: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl Default for ImpersonationSettings {
+    fn default() -> Self {
+        ImpersonationSettings {
+            enabled: true,
+            mapping_file: None,
+            shared_node_pod_counts: false,
+        }
+    }
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        AuthSettings {
+            tokens_file: None,
+            htpasswd_file: None,
+            oidc: None,
+            session_ttl: Duration::from_secs(8 * 60 * 60),
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_env() -> Self {
+        let oidc = var("OIDC_ISSUER").map(|issuer| OidcSettings {
+            issuer,
+            audience: var("OIDC_AUDIENCE"),
+            jwks_file: var("OIDC_JWKS_FILE").map(PathBuf::from),
+            jwks_url: var("OIDC_JWKS_URL"),
+            introspection_url: var("OIDC_INTROSPECTION_URL"),
+            client_id: var("OIDC_CLIENT_ID"),
+            client_secret: var("OIDC_CLIENT_SECRET"),
+            username_claim: var("OIDC_USERNAME_CLAIM").unwrap_or_else(|| "sub".to_string()),
+            groups_claim: var("OIDC_GROUPS_CLAIM").unwrap_or_else(|| "groups".to_string()),
+        });
+        let defaults = AuthSettings::default();
+
+        Settings {
+            auth: AuthSettings {
+                tokens_file: var("TOKENS_FILE").map(PathBuf::from),
+                htpasswd_file: var("HTPASSWD_FILE").map(PathBuf::from),
+                oidc,
+                session_ttl: seconds("SESSION_TTL_SECONDS").unwrap_or(defaults.session_ttl),
+            },
+            impersonation: ImpersonationSettings {
+                enabled: flag("IMPERSONATE").unwrap_or(true),
+                mapping_file: var("IDENTITY_MAP_FILE").map(PathBuf::from),
+                shared_node_pod_counts: flag("SHARED_NODE_POD_COUNTS").unwrap_or(false),
+            },
+            secrets: SecretSettings {
+                allow_reveal: flag("ALLOW_SECRET_REVEAL").unwrap_or(false),
+            },
+            drain: DrainSettings {
+                timeout: seconds("DRAIN_TIMEOUT_SECONDS").unwrap_or(DrainSettings::default().timeout),
+            },
+            metrics: MetricsSettings {
+                sample_interval: seconds("METRICS_SAMPLE_INTERVAL_SECONDS")
+                    .filter(|d| !d.is_zero())
+                    .unwrap_or(MetricsSettings::default().sample_interval),
+                retention: seconds("METRICS_RETENTION_SECONDS").unwrap_or(MetricsSettings::default().retention),
+            },
+            audit_log: var("AUDIT_LOG").map(PathBuf::from),
+        }
+    }
+}
+
+fn var(name: &str) -> Option<String> {
+    env::var(format!("{}{}", ENV_PREFIX, name))
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+}
+
+/// Reads a whole number of seconds; an invalid value is reported and the default used.
+fn seconds(name: &str) -> Option<Duration> {
+    let value = var(name)?;
+    match value.trim().parse() {
+        Ok(secs) => Some(Duration::from_secs(secs)),
+        Err(_) => {
+            eprintln!("Ignoring {}{}={:?}: expected a number of seconds", ENV_PREFIX, name, value);
+            None
+        }
+    }
+}
+
+fn flag(name: &str) -> Option<bool> {
+    var(name).map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+}
@@ -0,0 +1,589 @@
+// src/discovery.rs
+use futures::future::join_all;
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::{Api, Client, ResourceExt};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use warp::http::header::CONTENT_TYPE;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+use crate::cache::ResourceCache;
+use crate::error::{reject, ApiError};
+use crate::models::{ApiResourceInfo, PrinterColumn, ResourceQuery, ResourceRow, ResourceTable};
+use crate::yaml::to_yaml;
+
+/// How long discovery results are reused before asking the API server again,
+/// so newly installed CRDs show up without a restart.
+const CATALOG_TTL: Duration = Duration::from_secs(300);
+/// Path segment standing in for the core API group, whose name is empty.
+pub const CORE_GROUP: &str = "core";
+
+type Snapshot = (Instant, Arc<Vec<ApiResourceInfo>>);
+
+/// Every listable kind served by one cluster, discovered lazily and cached.
+#[derive(Clone, Default)]
+pub struct Catalog {
+    resources: Arc<RwLock<Option<Snapshot>>>,
+}
+
+impl Catalog {
+    pub async fn resources(&self, client: &Client) -> Result<Arc<Vec<ApiResourceInfo>>, ApiError> {
+        if let Some((loaded_at, resources)) = &*self.resources.read().await {
+            if loaded_at.elapsed() < CATALOG_TTL {
+                return Ok(resources.clone());
+            }
+        }
+        let resources = Arc::new(discover(client).await?);
+        *self.resources.write().await = Some((Instant::now(), resources.clone()));
+        Ok(resources)
+    }
+
+    /// Looks up a kind by its name or plural, e.g. `Certificate` or `certificates`.
+    pub async fn find(&self, client: &Client, group: &str, version: &str, kind: &str) -> Result<ApiResourceInfo, ApiError> {
+        let group = if group == CORE_GROUP { "" } else { group };
+        self.resources(client)
+            .await?
+            .iter()
+            .find(|r| {
+                r.group == group && r.version == version && (r.kind.eq_ignore_ascii_case(kind) || r.plural == kind)
+            })
+            .cloned()
+            .ok_or_else(|| ApiError::not_found(format!("No resource {} in {}/{}", kind, group, version)))
+    }
+}
+
+/// Queries every group version, skipping ones that fail (e.g. an unavailable
+/// aggregated API) instead of failing the whole catalog.
+async fn discover(client: &Client) -> Result<Vec<ApiResourceInfo>, ApiError> {
+    let mut group_versions = Vec::new();
+    let core = client.list_core_api_versions().await?;
+    for version in core.versions {
+        group_versions.push((String::new(), version));
+    }
+    let groups = client.list_api_groups().await?;
+    for group in groups.groups {
+        for version in group.versions {
+            group_versions.push((group.name.clone(), version.version));
+        }
+    }
+
+    let lists = join_all(group_versions.into_iter().map(|(group, version)| async move {
+        let result = if group.is_empty() {
+            client.list_core_api_resources(&version).await
+        } else {
+            client.list_api_group_resources(&format!("{}/{}", group, version)).await
+        };
+        (group, version, result)
+    }))
+    .await;
+
+    let mut resources = Vec::new();
+    for (group, version, result) in lists {
+        let list = match result {
+            Ok(list) => list,
+            Err(e) => {
+                eprintln!("Skipping discovery of {}/{}: {}", group, version, e);
+                continue;
+            }
+        };
+        // Subresources such as pods/log are listed as `plural/subresource`.
+        for resource in list.resources.into_iter().filter(|r| !r.name.contains('/')) {
+            resources.push(ApiResourceInfo {
+                group: group.clone(),
+                version: version.clone(),
+                kind: resource.kind,
+                plural: resource.name,
+                namespaced: resource.namespaced,
+                verbs: resource.verbs,
+                short_names: resource.short_names.unwrap_or_default(),
+            });
+        }
+    }
+    resources.sort_by(|a, b| (&a.group, &a.kind, &a.version).cmp(&(&b.group, &b.kind, &b.version)));
+    Ok(resources)
+}
+
+pub async fn get_api_resources(cache: ResourceCache) -> Result<impl Reply, Rejection> {
+    let resources = cache
+        .catalog()
+        .resources(&cache.client())
+        .await
+        .map_err(warp::reject::custom)?;
+    Ok(warp::reply::json(&*resources))
+}
+
+/// Lists or gets any discovered kind. `rest` holds the remaining path segments:
+/// nothing (all namespaces), `{ns}` or `{ns}/{name}` for namespaced kinds, and
+/// nothing or `{name}` for cluster-scoped ones.
+pub async fn get_dynamic_resources(
+    group: String,
+    version: String,
+    kind: String,
+    rest: Vec<String>,
+    query: ResourceQuery,
+    cache: ResourceCache,
+) -> Result<warp::reply::Response, Rejection> {
+    let client = cache.client();
+    let info = cache
+        .catalog()
+        .find(&client, &group, &version, &kind)
+        .await
+        .map_err(warp::reject::custom)?;
+    let resource = api_resource(&info);
+
+    let (namespace, name) = match (info.namespaced, rest.as_slice()) {
+        (_, []) => (None, None),
+        (true, [namespace]) => (Some(namespace), None),
+        (true, [namespace, name]) => (Some(namespace), Some(name)),
+        (false, [name]) => (None, Some(name)),
+        _ => return Err(warp::reject::not_found()),
+    };
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client.clone(), ns, &resource),
+        None => Api::all_with(client.clone(), &resource),
+    };
+
+    if let Some(name) = name {
+        let mut obj = api.get(name).await.map_err(reject)?;
+        obj.metadata.managed_fields = None;
+        redact_secret(&info, &mut obj);
+        return Ok(match query.output.as_deref() {
+            Some("yaml") => warp::reply::with_header(to_yaml(&obj)?, CONTENT_TYPE, "application/yaml").into_response(),
+            _ => warp::reply::json(&obj).into_response(),
+        });
+    }
+
+    if !info.verbs.iter().any(|v| v == "list") {
+        return Err(warp::reject::custom(ApiError::new(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "MethodNotAllowed",
+            format!("{} cannot be listed", info.plural),
+        )));
+    }
+    let mut list = api.list(&ListParams::default()).await.map_err(reject)?;
+    let columns = printer_columns(&client, &info).await;
+
+    let rows = list
+        .items
+        .iter_mut()
+        .map(|obj| {
+            redact_secret(&info, obj);
+            let value = serde_json::to_value(&*obj).unwrap_or(Value::Null);
+            ResourceRow {
+                name: obj.name_any(),
+                namespace: obj.namespace(),
+                created_at: obj.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+                cells: columns.iter().map(|c| json_path(&value, &c.json_path)).collect(),
+            }
+        })
+        .collect();
+
+    Ok(warp::reply::json(&ResourceTable {
+        resource: info,
+        columns,
+        rows,
+    })
+    .into_response())
+}
+
+/// Drops the values of core Secrets, which are only readable one key at a time
+/// through the gated and audited `reveal_secret`. The last-applied annotation
+/// goes too, as it holds a copy of the data.
+fn redact_secret(info: &ApiResourceInfo, obj: &mut DynamicObject) {
+    if !(info.group.is_empty() && info.plural == "secrets") {
+        return;
+    }
+    if let Some(fields) = obj.data.as_object_mut() {
+        fields.remove("data");
+        fields.remove("stringData");
+    }
+    if let Some(annotations) = obj.metadata.annotations.as_mut() {
+        annotations.remove("kubectl.kubernetes.io/last-applied-configuration");
+    }
+}
+
+pub fn api_resource(info: &ApiResourceInfo) -> ApiResource {
+    let api_version = if info.group.is_empty() {
+        info.version.clone()
+    } else {
+        format!("{}/{}", info.group, info.version)
+    };
+    ApiResource {
+        group: info.group.clone(),
+        version: info.version.clone(),
+        api_version,
+        kind: info.kind.clone(),
+        plural: info.plural.clone(),
+    }
+}
+
+/// Reads the CRD's additionalPrinterColumns for this version, skipping the
+/// wide-only ones (priority > 0). Built-in kinds and unreadable CRDs have none.
+async fn printer_columns(client: &Client, info: &ApiResourceInfo) -> Vec<PrinterColumn> {
+    if info.group.is_empty() {
+        return Vec::new();
+    }
+    let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
+    let crd = match crds.get_opt(&format!("{}.{}", info.plural, info.group)).await {
+        Ok(Some(crd)) => crd,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            eprintln!("Cannot read CRD for {}.{}: {}", info.plural, info.group, e);
+            return Vec::new();
+        }
+    };
+
+    crd.spec
+        .versions
+        .iter()
+        .find(|v| v.name == info.version)
+        .and_then(|v| v.additional_printer_columns.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|c| c.priority.unwrap_or(0) == 0)
+        .map(|c| PrinterColumn {
+            name: c.name.clone(),
+            column_type: c.type_.clone(),
+            json_path: c.json_path.clone(),
+            description: c.description.clone(),
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+enum PathStep {
+    Field(String),
+    Index(i64),
+    Wildcard,
+    /// `[?(@.path == 'value')]`, or `[?(@.path)]` for existence when `value` is `None`.
+    Filter {
+        path: String,
+        negate: bool,
+        value: Option<String>,
+    },
+}
+
+/// Evaluates the JSONPath subset used by printer columns: `.field`, `['field']`,
+/// `[index]`, `[*]` and `[?(@.field == "value")]`. Several matches become an array.
+fn json_path(value: &Value, path: &str) -> Value {
+    let mut current = vec![value];
+    for step in parse_json_path(path) {
+        current = current
+            .into_iter()
+            .flat_map(|v| apply_step(v, &step))
+            .collect();
+    }
+    match current.len() {
+        0 => Value::Null,
+        1 => current[0].clone(),
+        _ => Value::Array(current.into_iter().cloned().collect()),
+    }
+}
+
+fn apply_step<'a>(value: &'a Value, step: &PathStep) -> Vec<&'a Value> {
+    match step {
+        PathStep::Field(name) => value.get(name).into_iter().collect(),
+        PathStep::Index(index) => {
+            let Some(items) = value.as_array() else {
+                return Vec::new();
+            };
+            let index = if *index < 0 { items.len() as i64 + index } else { *index };
+            usize::try_from(index).ok().and_then(|i| items.get(i)).into_iter().collect()
+        }
+        PathStep::Wildcard => match value {
+            Value::Array(items) => items.iter().collect(),
+            Value::Object(map) => map.values().collect(),
+            _ => Vec::new(),
+        },
+        PathStep::Filter { path, negate, value: expected } => value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|item| {
+                let found = json_path(item, path);
+                let matched = match expected {
+                    Some(expected) => value_to_string(&found) == *expected,
+                    None => !found.is_null(),
+                };
+                matched != *negate
+            })
+            .collect(),
+    }
+}
+
+fn parse_json_path(path: &str) -> Vec<PathStep> {
+    let path = path.trim().trim_start_matches('{').trim_end_matches('}');
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let chars: Vec<char> = path.chars().collect();
+    let mut steps = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                let start = i + 1;
+                i = start;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                let field: String = chars[start..i].iter().collect();
+                if !field.is_empty() {
+                    steps.push(if field == "*" { PathStep::Wildcard } else { PathStep::Field(field) });
+                }
+            }
+            '[' => {
+                let start = i + 1;
+                let mut depth = 0;
+                let mut quote = None;
+                i = start;
+                while i < chars.len() {
+                    match (chars[i], quote) {
+                        ('\'' | '"', None) => quote = Some(chars[i]),
+                        (c, Some(q)) if c == q => quote = None,
+                        ('(', None) => depth += 1,
+                        (')', None) => depth -= 1,
+                        (']', None) if depth == 0 => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                let inner: String = chars[start..i.min(chars.len())].iter().collect();
+                i += 1;
+                steps.push(parse_bracket(inner.trim()));
+            }
+            _ => {
+                // A path without a leading dot, e.g. `spec.replicas`.
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                steps.push(PathStep::Field(chars[start..i].iter().collect()));
+            }
+        }
+    }
+    steps
+}
+
+fn parse_bracket(inner: &str) -> PathStep {
+    if inner == "*" {
+        return PathStep::Wildcard;
+    }
+    if let Ok(index) = inner.parse() {
+        return PathStep::Index(index);
+    }
+    if let Some(expr) = inner.strip_prefix("?(").and_then(|e| e.strip_suffix(')')) {
+        let expr = expr.trim();
+        for (op, negate) in [("!=", true), ("==", false)] {
+            if let Some((lhs, rhs)) = expr.split_once(op) {
+                return PathStep::Filter {
+                    path: lhs.trim().trim_start_matches('@').to_string(),
+                    negate,
+                    value: Some(unquote(rhs.trim()).to_string()),
+                };
+            }
+        }
+        return PathStep::Filter {
+            path: expr.trim_start_matches('@').to_string(),
+            negate: false,
+            value: None,
+        };
+    }
+    PathStep::Field(unquote(inner).to_string())
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+        .unwrap_or(s)
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn widget() -> Value {
+        json!({
+            "metadata": {
+                "name": "w1",
+                "annotations": { "example.com/owner": "team-a" }
+            },
+            "spec": { "replicas": 3, "ports": [80, 443, 8080] },
+            "status": {
+                "conditions": [
+                    { "type": "Synced", "status": "False" },
+                    { "type": "Ready", "status": "True" }
+                ]
+            }
+        })
+    }
+
+    fn resource_info(group: &str, kind: &str, plural: &str) -> ApiResourceInfo {
+        ApiResourceInfo {
+            group: group.to_string(),
+            version: "v1".to_string(),
+            kind: kind.to_string(),
+            plural: plural.to_string(),
+            namespaced: true,
+            verbs: vec!["get".to_string(), "list".to_string()],
+            short_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn secrets_carry_no_values() {
+        let mut secret: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "Secret",
+            "metadata": {
+                "name": "db",
+                "namespace": "default",
+                "annotations": {
+                    "kubectl.kubernetes.io/last-applied-configuration": "{\"data\":{\"password\":\"aHVudGVyMg==\"}}",
+                    "owner": "team-a"
+                }
+            },
+            "type": "Opaque",
+            "data": { "password": "aHVudGVyMg==" },
+            "stringData": { "user": "admin" }
+        }))
+        .unwrap();
+        redact_secret(&resource_info("", "Secret", "secrets"), &mut secret);
+
+        let value = serde_json::to_value(&secret).unwrap();
+        assert_eq!(value.get("data"), None);
+        assert_eq!(value.get("stringData"), None);
+        assert_eq!(value["type"], json!("Opaque"));
+        assert_eq!(value["metadata"]["annotations"], json!({ "owner": "team-a" }));
+        assert!(!serde_json::to_string(&value).unwrap().contains("aHVudGVyMg=="));
+    }
+
+    #[test]
+    fn other_kinds_keep_their_data() {
+        let mut config: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "settings", "namespace": "default" },
+            "data": { "mode": "fast" }
+        }))
+        .unwrap();
+        redact_secret(&resource_info("", "ConfigMap", "configmaps"), &mut config);
+        assert_eq!(config.data["data"], json!({ "mode": "fast" }));
+
+        let mut custom: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "example.com/v1",
+            "kind": "Secret",
+            "metadata": { "name": "s", "namespace": "default" },
+            "data": { "token": "kept" }
+        }))
+        .unwrap();
+        redact_secret(&resource_info("example.com", "Secret", "secrets"), &mut custom);
+        assert_eq!(custom.data["data"], json!({ "token": "kept" }));
+    }
+
+    #[test]
+    fn parses_dotted_and_bracketed_steps() {
+        assert_eq!(
+            parse_json_path(".spec.replicas"),
+            vec![PathStep::Field("spec".into()), PathStep::Field("replicas".into())]
+        );
+        assert_eq!(
+            parse_json_path(".metadata.annotations['example.com/owner']"),
+            vec![
+                PathStep::Field("metadata".into()),
+                PathStep::Field("annotations".into()),
+                PathStep::Field("example.com/owner".into()),
+            ]
+        );
+        assert_eq!(
+            parse_json_path(".items[0][-1][*].*"),
+            vec![
+                PathStep::Field("items".into()),
+                PathStep::Index(0),
+                PathStep::Index(-1),
+                PathStep::Wildcard,
+                PathStep::Wildcard,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_filters() {
+        assert_eq!(
+            parse_json_path(r#".conditions[?(@.type=="Ready")].status"#),
+            vec![
+                PathStep::Field("conditions".into()),
+                PathStep::Filter { path: ".type".into(), negate: false, value: Some("Ready".into()) },
+                PathStep::Field("status".into()),
+            ]
+        );
+        assert_eq!(
+            parse_json_path(".conditions[?(@.type != 'Ready')]")[1],
+            PathStep::Filter { path: ".type".into(), negate: true, value: Some("Ready".into()) }
+        );
+        assert_eq!(
+            parse_json_path(".conditions[?(@.reason)]")[1],
+            PathStep::Filter { path: ".reason".into(), negate: false, value: None }
+        );
+    }
+
+    #[test]
+    fn accepts_paths_without_leading_dot_or_braces() {
+        let expected = vec![PathStep::Field("spec".into()), PathStep::Field("replicas".into())];
+        assert_eq!(parse_json_path("spec.replicas"), expected);
+        assert_eq!(parse_json_path("{.spec.replicas}"), expected);
+        assert_eq!(parse_json_path("{$.spec.replicas}"), expected);
+        assert_eq!(parse_json_path(" $.spec.replicas "), expected);
+    }
+
+    #[test]
+    fn applies_steps() {
+        let value = json!({ "a": [1, 2, 3], "b": { "x": 1, "y": 2 } });
+        let a = &value["a"];
+        assert_eq!(apply_step(&value, &PathStep::Field("a".into())), vec![a]);
+        assert!(apply_step(&value, &PathStep::Field("missing".into())).is_empty());
+        assert_eq!(apply_step(a, &PathStep::Index(0)), vec![&json!(1)]);
+        assert_eq!(apply_step(a, &PathStep::Index(-1)), vec![&json!(3)]);
+        assert!(apply_step(a, &PathStep::Index(3)).is_empty());
+        assert!(apply_step(a, &PathStep::Index(-4)).is_empty());
+        assert!(apply_step(&value["b"], &PathStep::Index(0)).is_empty());
+        assert_eq!(apply_step(a, &PathStep::Wildcard).len(), 3);
+        assert_eq!(apply_step(&value["b"], &PathStep::Wildcard), vec![&json!(1), &json!(2)]);
+    }
+
+    #[test]
+    fn evaluates_printer_column_paths() {
+        let widget = widget();
+        assert_eq!(json_path(&widget, ".spec.replicas"), json!(3));
+        assert_eq!(json_path(&widget, "spec.replicas"), json!(3));
+        assert_eq!(json_path(&widget, ".metadata.annotations['example.com/owner']"), json!("team-a"));
+        assert_eq!(json_path(&widget, r#".metadata.annotations["example.com/owner"]"#), json!("team-a"));
+        assert_eq!(json_path(&widget, ".spec.ports[0]"), json!(80));
+        assert_eq!(json_path(&widget, ".spec.ports[-1]"), json!(8080));
+        assert_eq!(json_path(&widget, ".spec.ports[*]"), json!([80, 443, 8080]));
+        assert_eq!(json_path(&widget, ".spec.missing"), Value::Null);
+    }
+
+    #[test]
+    fn evaluates_filters() {
+        let widget = widget();
+        assert_eq!(json_path(&widget, r#".status.conditions[?(@.type=="Ready")].status"#), json!("True"));
+        assert_eq!(json_path(&widget, "{.status.conditions[?(@.type == 'Synced')].status}"), json!("False"));
+        assert_eq!(json_path(&widget, r#".status.conditions[?(@.type!="Ready")].type"#), json!("Synced"));
+        assert_eq!(json_path(&widget, ".status.conditions[?(@.status)].type"), json!(["Synced", "Ready"]));
+        assert_eq!(json_path(&widget, r#".status.conditions[?(@.type=="Gone")].status"#), Value::Null);
+    }
+}
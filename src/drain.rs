@@ -0,0 +1,192 @@
+// src/drain.rs
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::future::join_all;
+use futures::StreamExt;
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::ListParams;
+use kube::{Api, Client, ResourceExt};
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use warp::Rejection;
+
+use crate::audit::RequestAudit;
+use crate::config::Settings;
+use crate::error::ApiError;
+use crate::handlers::{request_eviction, set_unschedulable};
+use crate::models::{DrainEvent, DrainRequest};
+
+const MIRROR_POD_ANNOTATION: &str = "kubernetes.io/config.mirror";
+/// Delay between eviction attempts refused by a PodDisruptionBudget.
+const EVICTION_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay between checks for an evicted pod being gone.
+const DELETION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest timeout a drain request may ask for.
+const MAX_REQUEST_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Cordons the node and evicts its pods, streaming progress as server-sent events.
+/// The drain runs in its own task, so it finishes even if the client disconnects.
+pub async fn drain_node(
+    name: String,
+    request: DrainRequest,
+    settings: Settings,
+    audit: RequestAudit,
+    client: Client,
+) -> Result<impl warp::Reply, Rejection> {
+    let timeout = match request.timeout_seconds {
+        Some(seconds) if seconds > MAX_REQUEST_TIMEOUT.as_secs() => {
+            return Err(warp::reject::custom(ApiError::bad_request(format!(
+                "timeoutSeconds must be at most {}",
+                MAX_REQUEST_TIMEOUT.as_secs()
+            ))));
+        }
+        Some(seconds) => Duration::from_secs(seconds),
+        None => settings.drain.timeout,
+    };
+    let deadline = Instant::now()
+        .checked_add(timeout)
+        .ok_or_else(|| warp::reject::custom(ApiError::bad_request("Drain timeout is too large")))?;
+    let (tx, rx) = unbounded();
+
+    tokio::spawn(async move {
+        let outcome = match drain(&name, request.grace_period_seconds, deadline, &tx, client).await {
+            Ok((evicted, skipped, 0)) => {
+                let _ = tx.unbounded_send(DrainEvent::Done { evicted, skipped, failed: 0 });
+                "succeeded".to_string()
+            }
+            Ok((evicted, skipped, failed)) => {
+                let _ = tx.unbounded_send(DrainEvent::Done { evicted, skipped, failed });
+                format!("failed: {} pods not evicted", failed)
+            }
+            Err(e) => {
+                let _ = tx.unbounded_send(DrainEvent::Error { message: e.to_string() });
+                format!("error: {}", e)
+            }
+        };
+        audit.record("drain", &format!("nodes/{}", name), &outcome);
+    });
+
+    let events = rx.map(|event| warp::sse::Event::default().json_data(event));
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+}
+
+/// Returns the number of evicted, skipped and failed pods.
+async fn drain(
+    name: &str,
+    grace_period_seconds: Option<u32>,
+    deadline: Instant,
+    tx: &UnboundedSender<DrainEvent>,
+    client: Client,
+) -> Result<(usize, usize, usize), kube::Error> {
+    set_unschedulable(name, true, client.clone()).await?;
+    let _ = tx.unbounded_send(DrainEvent::Cordoned { node: name.to_string() });
+
+    let pods: Api<Pod> = Api::all(client.clone());
+    let pod_list = pods
+        .list(&ListParams::default().fields(&format!("spec.nodeName={}", name)))
+        .await?;
+
+    let mut skipped = 0;
+    let mut to_evict = Vec::new();
+    for pod in pod_list.items {
+        match skip_reason(&pod) {
+            Some(reason) => {
+                skipped += 1;
+                let _ = tx.unbounded_send(DrainEvent::Skipped {
+                    namespace: pod.namespace().unwrap_or_default(),
+                    name: pod.name_any(),
+                    reason: reason.to_string(),
+                });
+            }
+            None => to_evict.push(pod),
+        }
+    }
+
+    let results = join_all(
+        to_evict
+            .iter()
+            .map(|pod| evict_and_wait(pod, grace_period_seconds, deadline, tx, client.clone())),
+    )
+    .await;
+    let evicted = results.iter().filter(|evicted| **evicted).count();
+
+    Ok((evicted, skipped, results.len() - evicted))
+}
+
+fn skip_reason(pod: &Pod) -> Option<&'static str> {
+    if pod.annotations().contains_key(MIRROR_POD_ANNOTATION) {
+        return Some("mirror pod");
+    }
+    let daemon_set = pod
+        .owner_references()
+        .iter()
+        .any(|owner| owner.kind == "DaemonSet" && owner.controller == Some(true));
+    daemon_set.then_some("managed by a DaemonSet")
+}
+
+/// Evicts one pod, retrying while a PodDisruptionBudget refuses, then waits for it
+/// to be deleted. Returns whether the pod is gone before the deadline.
+async fn evict_and_wait(
+    pod: &Pod,
+    grace_period_seconds: Option<u32>,
+    deadline: Instant,
+    tx: &UnboundedSender<DrainEvent>,
+    client: Client,
+) -> bool {
+    let namespace = pod.namespace().unwrap_or_default();
+    let name = pod.name_any();
+    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+    let fail = |message: String| {
+        let _ = tx.unbounded_send(DrainEvent::Failed {
+            namespace: namespace.clone(),
+            name: name.clone(),
+            message,
+        });
+        false
+    };
+
+    let _ = tx.unbounded_send(DrainEvent::Evicting {
+        namespace: namespace.clone(),
+        name: name.clone(),
+    });
+    let mut blocked = false;
+    loop {
+        match request_eviction(&pods, &name, grace_period_seconds).await {
+            Ok(()) => break,
+            Err(e) if e.status.as_u16() == 404 => break,
+            Err(e) if e.reason == "DisruptionBudgetViolation" => {
+                if !blocked {
+                    blocked = true;
+                    let _ = tx.unbounded_send(DrainEvent::Blocked {
+                        namespace: namespace.clone(),
+                        name: name.clone(),
+                        message: e.message,
+                    });
+                }
+                if Instant::now() + EVICTION_RETRY_INTERVAL > deadline {
+                    return fail("Timed out waiting for the PodDisruptionBudget to allow eviction".to_string());
+                }
+                sleep(EVICTION_RETRY_INTERVAL).await;
+            }
+            Err(e) => return fail(e.message),
+        }
+    }
+
+    // A controller may recreate a pod with the same name, so compare UIDs.
+    loop {
+        match pods.get_opt(&name).await {
+            Ok(Some(current)) if current.uid() == pod.uid() => {}
+            Ok(_) => {
+                let _ = tx.unbounded_send(DrainEvent::Evicted {
+                    namespace: namespace.clone(),
+                    name: name.clone(),
+                });
+                return true;
+            }
+            Err(e) => return fail(e.to_string()),
+        }
+        if Instant::now() + DELETION_POLL_INTERVAL > deadline {
+            return fail("Timed out waiting for the pod to terminate".to_string());
+        }
+        sleep(DELETION_POLL_INTERVAL).await;
+    }
+}
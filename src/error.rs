@@ -0,0 +1,162 @@
+// src/error.rs
+use kube::core::ErrorResponse;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+
+/// Error returned by API handlers, rendered as a JSON body by `handle_rejection`.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub reason: String,
+    pub message: String,
+    pub details: Option<Box<ErrorResponse>>,
+}
+
+impl warp::reject::Reject for ApiError {}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody<'a> {
+    code: u16,
+    reason: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<&'a ErrorResponse>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, reason: &str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            reason: reason.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "BadRequest", message)
+    }
+
+    /// The caller failed to sign in to the dashboard itself. Kept distinct from the
+    /// `Unauthorized` reason of a 401 from the cluster, so the frontend only sends
+    /// the browser to the login page for this one.
+    pub fn unauthenticated(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "Unauthenticated", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "NotFound", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "InternalError", message)
+    }
+}
+
+impl From<kube::Error> for ApiError {
+    fn from(err: kube::Error) -> Self {
+        match err {
+            kube::Error::Api(resp) => {
+                let status = StatusCode::from_u16(resp.code).unwrap_or(StatusCode::BAD_GATEWAY);
+                let reason = if resp.reason.is_empty() {
+                    status.canonical_reason().unwrap_or("Unknown").replace(' ', "")
+                } else {
+                    resp.reason.clone()
+                };
+                ApiError {
+                    status,
+                    reason,
+                    message: resp.message.clone(),
+                    details: Some(Box::new(resp)),
+                }
+            }
+            kube::Error::Auth(e) => {
+                ApiError::new(StatusCode::UNAUTHORIZED, "Unauthorized", e.to_string())
+            }
+            kube::Error::HyperError(e) if e.is_timeout() => {
+                ApiError::new(StatusCode::GATEWAY_TIMEOUT, "Timeout", e.to_string())
+            }
+            kube::Error::HyperError(e) => {
+                ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "ServiceUnavailable", e.to_string())
+            }
+            kube::Error::Service(e) => {
+                if is_timeout(e.as_ref()) {
+                    ApiError::new(StatusCode::GATEWAY_TIMEOUT, "Timeout", e.to_string())
+                } else {
+                    ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "ServiceUnavailable", e.to_string())
+                }
+            }
+            kube::Error::SerdeError(e) => {
+                ApiError::new(StatusCode::BAD_GATEWAY, "InvalidResponse", e.to_string())
+            }
+            other => ApiError::internal(other.to_string()),
+        }
+    }
+}
+
+/// Walks the error chain looking for a timeout from hyper or std io.
+fn is_timeout(err: &(dyn StdError + 'static)) -> bool {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            if io.kind() == std::io::ErrorKind::TimedOut {
+                return true;
+            }
+        }
+        if let Some(h) = e.downcast_ref::<hyper::Error>() {
+            if h.is_timeout() {
+                return true;
+            }
+        }
+        current = e.source();
+    }
+    false
+}
+
+/// Converts a kube error into a warp rejection carrying an `ApiError`.
+pub fn reject(err: kube::Error) -> Rejection {
+    warp::reject::custom(ApiError::from(err))
+}
+
+pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+    let fallback;
+    let error = if let Some(e) = err.find::<ApiError>() {
+        e
+    } else {
+        fallback = if err.is_not_found() {
+            ApiError::not_found("Route not found")
+        } else if let Some(e) = err.find::<warp::reject::InvalidQuery>() {
+            ApiError::bad_request(e.to_string())
+        } else if let Some(e) = err.find::<warp::body::BodyDeserializeError>() {
+            ApiError::bad_request(e.to_string())
+        } else if let Some(e) = err.find::<warp::reject::MissingHeader>() {
+            // e.g. a plain GET on a WebSocket route, which needs the upgrade headers
+            ApiError::bad_request(e.to_string())
+        } else if let Some(e) = err.find::<warp::reject::InvalidHeader>() {
+            ApiError::bad_request(e.to_string())
+        } else if err.find::<warp::reject::UnsupportedMediaType>().is_some() {
+            ApiError::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, "UnsupportedMediaType", "Unsupported Content-Type")
+        } else if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+            ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "RequestEntityTooLarge", "Request body is too large")
+        } else if err.find::<warp::reject::LengthRequired>().is_some() {
+            ApiError::new(StatusCode::LENGTH_REQUIRED, "LengthRequired", "A Content-Length header is required")
+        } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
+            ApiError::new(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed", "Method not allowed")
+        } else {
+            eprintln!("Unhandled rejection: {:?}", err);
+            ApiError::internal("Unhandled rejection")
+        };
+        &fallback
+    };
+
+    let body = ErrorBody {
+        code: error.status.as_u16(),
+        reason: &error.reason,
+        message: &error.message,
+        status: error.details.as_deref(),
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&body), error.status))
+}
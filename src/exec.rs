@@ -0,0 +1,113 @@
+// src/exec.rs
+use futures::{SinkExt, StreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use kube::api::{AttachParams, AttachedProcess, TerminalSize};
+use kube::{Api, Client};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use warp::ws::{Message, WebSocket};
+use warp::Rejection;
+
+use crate::error::reject;
+use crate::models::ExecQuery;
+
+const DEFAULT_SHELL: &str = "/bin/sh";
+/// First byte of binary frames sent to the browser, mirroring the Kubernetes channel numbers.
+const STDOUT_CHANNEL: u8 = 1;
+
+/// Control messages sent by the browser terminal.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ExecInput {
+    Stdin { data: String },
+    Resize { cols: u16, rows: u16 },
+}
+
+pub async fn exec_pod(
+    namespace: String,
+    pod_name: String,
+    query: ExecQuery,
+    ws: warp::ws::Ws,
+    client: Client,
+) -> Result<impl warp::Reply, Rejection> {
+    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+
+    let command: Vec<String> = query
+        .command
+        .as_deref()
+        .unwrap_or(DEFAULT_SHELL)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let mut params = AttachParams::interactive_tty();
+    if let Some(container) = query.container {
+        params = params.container(container);
+    }
+
+    // Start the process before upgrading so failures (missing pod, RBAC, bad
+    // container) come back as a normal HTTP error.
+    let process = pods.exec(&pod_name, command, &params).await.map_err(reject)?;
+
+    Ok(ws.on_upgrade(move |socket| bridge_process(socket, process)))
+}
+
+async fn bridge_process(socket: WebSocket, mut process: AttachedProcess) {
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let (Some(mut stdin), Some(mut stdout)) = (process.stdin(), process.stdout()) else {
+        let _ = ws_tx.send(Message::close_with(1011u16, "process streams unavailable")).await;
+        return;
+    };
+    let mut resize = process.terminal_size();
+    let status = process.take_status();
+    let mut buf = vec![0u8; 8192];
+    let mut exited = false;
+
+    loop {
+        tokio::select! {
+            read = stdout.read(&mut buf[1..]) => match read {
+                Ok(0) | Err(_) => {
+                    exited = true;
+                    break;
+                }
+                Ok(n) => {
+                    buf[0] = STDOUT_CHANNEL;
+                    if ws_tx.send(Message::binary(buf[..=n].to_vec())).await.is_err() {
+                        break;
+                    }
+                }
+            },
+            msg = ws_rx.next() => match msg {
+                Some(Ok(msg)) if msg.is_text() => {
+                    match serde_json::from_str::<ExecInput>(msg.to_str().unwrap_or_default()) {
+                        Ok(ExecInput::Stdin { data }) => {
+                            if stdin.write_all(data.as_bytes()).await.is_err() {
+                                break;
+                            }
+                        }
+                        Ok(ExecInput::Resize { cols, rows }) => {
+                            if let Some(resize) = resize.as_mut() {
+                                let _ = resize.send(TerminalSize { width: cols, height: rows }).await;
+                            }
+                        }
+                        Err(e) => eprintln!("Invalid exec message: {}", e),
+                    }
+                }
+                Some(Ok(msg)) if msg.is_close() => break,
+                Some(Ok(_)) => {}
+                _ => break,
+            },
+        }
+    }
+
+    if !exited {
+        // The browser went away first; stop the process instead of leaving it attached.
+        process.abort();
+        return;
+    }
+    if let Some(status) = status {
+        let status = status.await;
+        let exit = serde_json::json!({ "type": "exit", "status": status });
+        let _ = ws_tx.send(Message::text(exit.to_string())).await;
+    }
+    let _ = ws_tx.send(Message::close()).await;
+}
@@ -1,339 +1,1799 @@
 // src/handlers.rs
 use anyhow::Result;
-use k8s_openapi::api::core::v1::{Namespace, Pod, Service, ConfigMap};
-use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{
+    Namespace, Node, Pod, Service, ConfigMap, Secret, Event, Container, ResourceRequirements, PodTemplateSpec, Toleration,
+};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::discovery::v1::EndpointSlice;
+use k8s_openapi::api::policy::v1::Eviction;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{DeleteOptions, ObjectMeta, Time};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use k8s_openapi::chrono::Utc;
 use k8s_openapi::api::networking::v1::NetworkPolicy;
-use kube::{Api, Client, ResourceExt};
-use std::convert::Infallible;
+use futures::{AsyncBufRead, AsyncBufReadExt, SinkExt, StreamExt};
+use kube::api::{DeleteParams, ListParams, LogParams, Patch, PatchParams, PostParams};
+use kube::{Api, Client, Resource, ResourceExt};
+use warp::ws::{Message, WebSocket};
+use std::collections::{BTreeMap, BTreeSet};
+use warp::http::StatusCode;
+use warp::{Rejection, Reply};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
 
+use crate::audit::RequestAudit;
+use crate::cache::{cached_json, CacheStatus, ResourceCache, ResourceKind};
+use crate::config::Settings;
+use crate::error::{reject, ApiError};
+use crate::k8s_client::{format_selector, ClusterRegistry};
+use crate::metrics;
 use crate::models::*;
 
-pub async fn get_namespaces(client: Client) -> Result<impl warp::Reply, Infallible> {
-    let namespaces: Api<Namespace> = Api::all(client);
+/// Field manager recorded on every write made through the dashboard.
+pub const FIELD_MANAGER: &str = "kube-inspector";
+const RESTARTED_AT_ANNOTATION: &str = "kubectl.kubernetes.io/restartedAt";
+const REVISION_ANNOTATION: &str = "deployment.kubernetes.io/revision";
+/// Set to `manual` on jobs created from a CronJob outside its schedule, as kubectl does.
+const CRONJOB_INSTANTIATE_ANNOTATION: &str = "cronjob.kubernetes.io/instantiate";
+
+pub async fn get_contexts(registry: ClusterRegistry) -> Result<impl warp::Reply, Rejection> {
+    Ok(warp::reply::json(&registry.contexts()))
+}
+
+pub async fn get_namespaces(cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::Namespaces, None) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let namespaces: Api<Namespace> = Api::all(cache.client());
     
-    match namespaces.list(&Default::default()).await {
-        Ok(namespace_list) => {
-            let ns_info: Vec<NamespaceInfo> = namespace_list
-                .items
-                .into_iter()
-                .map(|ns| NamespaceInfo {
-                    name: ns.name_any(),
-                    status: ns.status.as_ref().and_then(|s| s.phase.as_ref()).unwrap_or(&String::new()).clone(),
-                    created_at: ns.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                    labels: ns.labels().clone(),
-                })
-                .collect();
-            
-            Ok(warp::reply::json(&ns_info))
-        }
-        Err(e) => {
-            eprintln!("Error fetching namespaces: {}", e);
-            Ok(warp::reply::json(&Vec::<NamespaceInfo>::new()))
+    let namespace_list = namespaces.list(&Default::default()).await.map_err(reject)?;
+
+    let ns_info: Vec<NamespaceInfo> = namespace_list.items.iter().map(namespace_info).collect();
+    
+    Ok(cached_json(&ns_info, CacheStatus::Miss))
+}
+
+/// Lists pods, attaching live usage when metrics-server is available.
+pub async fn get_pods(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let (mut pods_info, status) = match cache.list(ResourceKind::Pods, Some(&namespace)) {
+        Some(cached) => (from_values::<PodInfo>(cached.objects), cached.status),
+        None => {
+            let pods: Api<Pod> = Api::namespaced(cache.client(), &namespace);
+
+            let pod_list = pods.list(&Default::default()).await.map_err(reject)?;
+
+            (pod_list.items.iter().map(pod_info).collect(), CacheStatus::Miss)
         }
-    }
+    };
+
+    attach_pod_usage(&cache, Some(&namespace), &mut pods_info).await;
+
+    Ok(cached_json(&pods_info, status))
 }
 
-pub async fn get_pods(namespace: String, client: Client) -> Result<impl warp::Reply, Infallible> {
-    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+pub async fn get_services(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::Services, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let services: Api<Service> = Api::namespaced(cache.client(), &namespace);
     
-    match pods.list(&Default::default()).await {
-        Ok(pod_list) => {
-            let pod_info: Vec<PodInfo> = pod_list
-                .items
-                .into_iter()
-                .map(|pod| {
-                    let status = pod.status.as_ref();
-                    let phase = status
-                        .and_then(|s| s.phase.as_ref())
-                        .unwrap_or(&"Unknown".to_string())
-                        .clone();
-                    
-                    let ready_containers = status
-                        .and_then(|s| s.container_statuses.as_ref())
-                        .map(|cs| cs.iter().filter(|c| c.ready).count())
-                        .unwrap_or(0);
-                    
-                    let total_containers = status
-                        .and_then(|s| s.container_statuses.as_ref())
-                        .map(|cs| cs.len())
-                        .unwrap_or(0);
-
-                    PodInfo {
-                        name: pod.name_any(),
-                        namespace: pod.namespace().unwrap_or_default(),
-                        phase,
-                        ready: format!("{}/{}", ready_containers, total_containers),
-                        restarts: status
-                            .and_then(|s| s.container_statuses.as_ref())
-                            .map(|cs| cs.iter().map(|c| c.restart_count).sum::<i32>())
-                            .unwrap_or(0),
-                        created_at: pod.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                        node_name: status.and_then(|s| s.host_ip.clone()),
-                        labels: pod.labels().clone(),
+    let service_list = services.list(&Default::default()).await.map_err(reject)?;
+
+    let services_info: Vec<ServiceInfo> = service_list.items.iter().map(service_info).collect();
+    
+    Ok(cached_json(&services_info, CacheStatus::Miss))
+}
+
+/// Service details with the pods its selector matches and the addresses its
+/// EndpointSlices publish, split into ready and not-ready per port.
+pub async fn get_service(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let services: Api<Service> = Api::namespaced(client.clone(), &namespace);
+    let slices: Api<EndpointSlice> = Api::namespaced(client.clone(), &namespace);
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+
+    let svc = services.get(&name).await.map_err(reject)?;
+    let spec = svc.spec.as_ref();
+    let selector = spec
+        .and_then(|s| s.selector.as_ref())
+        .map(|labels| labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(","))
+        .unwrap_or_default();
+
+    // A service without a selector has manually managed endpoints and no pods of its own
+    let mut matched_pods = if selector.is_empty() {
+        Vec::new()
+    } else {
+        let list = pods.list(&ListParams::default().labels(&selector)).await.map_err(reject)?;
+        list.items.iter().map(pod_info).collect()
+    };
+    attach_pod_usage(&cache, Some(&namespace), &mut matched_pods).await;
+
+    let slice_list = slices
+        .list(&ListParams::default().labels(&format!("kubernetes.io/service-name={}", name)))
+        .await
+        .map_err(reject)?;
+    let endpoints = endpoint_ports(&slice_list.items);
+    let ready_endpoints = endpoints
+        .iter()
+        .flat_map(|port| port.ready.iter().map(|a| a.address.as_str()))
+        .collect::<BTreeSet<_>>()
+        .len();
+
+    let details = ServiceDetails {
+        service: service_info(&svc),
+        selector,
+        external_name: spec.and_then(|s| s.external_name.clone()),
+        session_affinity: spec
+            .and_then(|s| s.session_affinity.clone())
+            .unwrap_or_else(|| "None".to_string()),
+        service_ports: spec
+            .and_then(|s| s.ports.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|p| ServicePortInfo {
+                name: p.name.clone(),
+                port: p.port,
+                target_port: p.target_port.as_ref().map(int_or_string),
+                node_port: p.node_port,
+                protocol: p.protocol.clone().unwrap_or_else(|| "TCP".to_string()),
+            })
+            .collect(),
+        ready_endpoints,
+        endpoints,
+        pods: matched_pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+/// Merges the endpoints of all slices by port. Every endpoint in a slice serves
+/// all of that slice's ports; a slice without ports serves all ports of its addresses.
+fn endpoint_ports(slices: &[EndpointSlice]) -> Vec<EndpointPortInfo> {
+    let mut ports: Vec<EndpointPortInfo> = Vec::new();
+    for slice in slices {
+        let slice_ports = match slice.ports.as_deref() {
+            Some(ports) if !ports.is_empty() => ports.to_vec(),
+            _ => vec![Default::default()],
+        };
+        for slice_port in slice_ports {
+            let protocol = slice_port.protocol.unwrap_or_else(|| "TCP".to_string());
+            let index = match ports
+                .iter()
+                .position(|p| p.name == slice_port.name && p.port == slice_port.port && p.protocol == protocol)
+            {
+                Some(index) => index,
+                None => {
+                    ports.push(EndpointPortInfo {
+                        name: slice_port.name,
+                        port: slice_port.port,
+                        protocol,
+                        ready: Vec::new(),
+                        not_ready: Vec::new(),
+                    });
+                    ports.len() - 1
+                }
+            };
+            for endpoint in &slice.endpoints {
+                let conditions = endpoint.conditions.as_ref();
+                // Per the API, an unknown ready state is treated as ready
+                let ready = conditions.and_then(|c| c.ready).unwrap_or(true);
+                for address in &endpoint.addresses {
+                    let info = EndpointAddressInfo {
+                        address: address.clone(),
+                        pod: endpoint
+                            .target_ref
+                            .as_ref()
+                            .filter(|r| r.kind.as_deref() == Some("Pod"))
+                            .and_then(|r| r.name.clone()),
+                        node: endpoint.node_name.clone(),
+                        serving: conditions.and_then(|c| c.serving).unwrap_or(ready),
+                        terminating: conditions.and_then(|c| c.terminating).unwrap_or(false),
+                    };
+                    if ready {
+                        ports[index].ready.push(info);
+                    } else {
+                        ports[index].not_ready.push(info);
                     }
-                })
-                .collect();
-            
-            Ok(warp::reply::json(&pod_info))
-        }
-        Err(e) => {
-            eprintln!("Error fetching pods: {}", e);
-            Ok(warp::reply::json(&Vec::<PodInfo>::new()))
+                }
+            }
         }
     }
+    ports
 }
 
-pub async fn get_services(namespace: String, client: Client) -> Result<impl warp::Reply, Infallible> {
-    let services: Api<Service> = Api::namespaced(client, &namespace);
+pub async fn get_deployments(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::Deployments, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let deployments: Api<Deployment> = Api::namespaced(cache.client(), &namespace);
     
-    match services.list(&Default::default()).await {
-        Ok(service_list) => {
-            let service_info: Vec<ServiceInfo> = service_list
-                .items
-                .into_iter()
-                .map(|svc| {
-                    let spec = svc.spec.as_ref();
-                    let ports = spec
-                        .and_then(|s| s.ports.as_ref())
-                        .map(|ports| {
-                            ports
-                                .iter()
-                                .map(|p| {
-                                    let target_port = p.target_port.as_ref()
-                                        .map(|tp| match tp {
-                                            k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(i) => i.to_string(),
-                                            k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::String(s) => s.clone(),
-                                        })
-                                        .unwrap_or_default();
-                                    format!("{}:{}", p.port, target_port)
-                                })
-                                .collect::<Vec<_>>()
-                                .join(", ")
-                        })
-                        .unwrap_or_default();
+    let deployment_list = deployments.list(&Default::default()).await.map_err(reject)?;
 
-                    ServiceInfo {
-                        name: svc.name_any(),
-                        namespace: svc.namespace().unwrap_or_default(),
-                        service_type: spec
-                            .and_then(|s| s.type_.as_ref())
-                            .unwrap_or(&"ClusterIP".to_string())
-                            .clone(),
-                        cluster_ip: spec
-                            .and_then(|s| s.cluster_ip.as_ref())
-                            .unwrap_or(&"None".to_string())
-                            .clone(),
-                        external_ip: spec
-                            .and_then(|s| s.external_ips.as_ref())
-                            .and_then(|ips| ips.first())
-                            .unwrap_or(&"<none>".to_string())
-                            .clone(),
-                        ports,
-                        created_at: svc.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                        labels: svc.labels().clone(),
-                    }
-                })
-                .collect();
-            
-            Ok(warp::reply::json(&service_info))
-        }
-        Err(e) => {
-            eprintln!("Error fetching services: {}", e);
-            Ok(warp::reply::json(&Vec::<ServiceInfo>::new()))
+    let deployments_info: Vec<DeploymentInfo> = deployment_list.items.iter().map(deployment_info).collect();
+    
+    Ok(cached_json(&deployments_info, CacheStatus::Miss))
+}
+
+pub async fn scale_deployment(namespace: String, name: String, request: ScaleRequest, client: Client) -> Result<impl warp::Reply, Rejection> {
+    if request.replicas < 0 {
+        return Err(warp::reject::custom(ApiError::bad_request("replicas must not be negative")));
+    }
+    let deployments: Api<Deployment> = Api::namespaced(client, &namespace);
+
+    let patch = serde_json::json!({ "spec": { "replicas": request.replicas } });
+    deployments
+        .patch_scale(&name, &patch_params(), &Patch::Merge(&patch))
+        .await
+        .map_err(reject)?;
+
+    let dep = deployments.get(&name).await.map_err(reject)?;
+    Ok(warp::reply::json(&deployment_info(&dep)))
+}
+
+/// Returns a deployment with its rollout state, owned ReplicaSets and current pods.
+pub async fn get_deployment(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), &namespace);
+    let replica_sets: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+
+    let dep = deployments.get(&name).await.map_err(reject)?;
+    let spec = dep.spec.as_ref();
+    let selector = spec.map(|s| format_selector(&s.selector)).unwrap_or_default();
+
+    let mut owned = owned_replica_sets(&dep, &replica_sets).await.map_err(reject)?;
+    owned.sort_by_key(|rs| std::cmp::Reverse(revision_of(rs.annotations()).unwrap_or(0)));
+    let pod_list = pods.list(&ListParams::default().labels(&selector)).await.map_err(reject)?;
+
+    // Pods are matched to ReplicaSets by owner UID, so pods that merely share labels are left out
+    let owner_of = |pod: &Pod| {
+        owned.iter().position(|rs| {
+            let uid = rs.uid().unwrap_or_default();
+            pod.owner_references().iter().any(|owner| owner.uid == uid)
+        })
+    };
+    let mut pod_counts = vec![0; owned.len()];
+    let mut owned_pods = Vec::new();
+    for pod in &pod_list.items {
+        if let Some(index) = owner_of(pod) {
+            pod_counts[index] += 1;
+            owned_pods.push(pod_info(pod));
         }
     }
+    attach_pod_usage(&cache, Some(&namespace), &mut owned_pods).await;
+
+    let rolling = spec
+        .and_then(|s| s.strategy.as_ref())
+        .and_then(|s| s.rolling_update.as_ref());
+    let details = DeploymentDetails {
+        deployment: deployment_info(&dep),
+        annotations: dep.annotations().clone(),
+        strategy: spec
+            .and_then(|s| s.strategy.as_ref())
+            .and_then(|s| s.type_.clone())
+            .unwrap_or_else(|| "RollingUpdate".to_string()),
+        max_surge: rolling.and_then(|r| r.max_surge.as_ref()).map(int_or_string),
+        max_unavailable: rolling.and_then(|r| r.max_unavailable.as_ref()).map(int_or_string),
+        selector,
+        revision: revision_of(dep.annotations()),
+        paused: spec.and_then(|s| s.paused).unwrap_or(false),
+        conditions: conditions_of(dep.status.as_ref().and_then(|s| s.conditions.as_ref())),
+        containers: containers_of(spec.map(|s| &s.template)),
+        replica_sets: owned
+            .iter()
+            .zip(pod_counts)
+            .map(|(rs, count)| ReplicaSetInfo {
+                pod_count: Some(count),
+                ..replica_set_info(rs)
+            })
+            .collect(),
+        pods: owned_pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+fn int_or_string(value: &IntOrString) -> String {
+    match value {
+        IntOrString::Int(i) => i.to_string(),
+        IntOrString::String(s) => s.clone(),
+    }
 }
 
-pub async fn get_deployments(namespace: String, client: Client) -> Result<impl warp::Reply, Infallible> {
+/// Triggers a rollout the same way `kubectl rollout restart` does, by stamping
+/// the pod template with a restart annotation.
+pub async fn restart_deployment(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
     let deployments: Api<Deployment> = Api::namespaced(client, &namespace);
-    
-    match deployments.list(&Default::default()).await {
-        Ok(deployment_list) => {
-            let deployment_info: Vec<DeploymentInfo> = deployment_list
-                .items
-                .into_iter()
-                .map(|dep| {
-                    let status = dep.status.as_ref();
-                    let spec = dep.spec.as_ref();
-
-                    DeploymentInfo {
-                        name: dep.name_any(),
-                        namespace: dep.namespace().unwrap_or_default(),
-                        ready_replicas: status
-                            .and_then(|s| s.ready_replicas)
-                            .unwrap_or(0),
-                        replicas: spec
-                            .and_then(|s| s.replicas)
-                            .unwrap_or(0),
-                        updated_replicas: status
-                            .and_then(|s| s.updated_replicas)
-                            .unwrap_or(0),
-                        available_replicas: status
-                            .and_then(|s| s.available_replicas)
-                            .unwrap_or(0),
-                        created_at: dep.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                        labels: dep.labels().clone(),
+
+    let patch = serde_json::json!({
+        "spec": {
+            "template": {
+                "metadata": {
+                    "annotations": {
+                        RESTARTED_AT_ANNOTATION: Utc::now().to_rfc3339()
                     }
-                })
-                .collect();
-            
-            Ok(warp::reply::json(&deployment_info))
-        }
-        Err(e) => {
-            eprintln!("Error fetching deployments: {}", e);
-            Ok(warp::reply::json(&Vec::<DeploymentInfo>::new()))
+                }
+            }
         }
+    });
+    let dep = deployments
+        .patch(&name, &patch_params(), &Patch::Strategic(&patch))
+        .await
+        .map_err(reject)?;
+
+    Ok(warp::reply::json(&deployment_info(&dep)))
+}
+
+/// Rolls back to the pod template of an owned ReplicaSet, like `kubectl rollout undo`.
+/// Without an explicit revision the most recent revision before the current one is used.
+pub async fn rollback_deployment(namespace: String, name: String, request: RollbackRequest, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let deployments: Api<Deployment> = Api::namespaced(client.clone(), &namespace);
+    let replica_sets: Api<ReplicaSet> = Api::namespaced(client, &namespace);
+
+    let mut dep = deployments.get(&name).await.map_err(reject)?;
+    let current_revision = revision_of(dep.annotations()).unwrap_or(0);
+
+    let mut owned = owned_replica_sets(&dep, &replica_sets).await.map_err(reject)?;
+    owned.sort_by_key(|rs| std::cmp::Reverse(revision_of(rs.annotations()).unwrap_or(0)));
+
+    let target = match request.revision {
+        Some(revision) => owned.iter().find(|rs| revision_of(rs.annotations()) == Some(revision)),
+        None => owned.iter().find(|rs| revision_of(rs.annotations()).unwrap_or(0) < current_revision),
+    };
+    let Some(target) = target else {
+        let message = match request.revision {
+            Some(revision) => format!("Revision {} not found for deployment {}", revision, name),
+            None => format!("No previous revision found for deployment {}", name),
+        };
+        return Err(warp::reject::custom(ApiError::not_found(message)));
+    };
+    if revision_of(target.annotations()) == Some(current_revision) {
+        return Err(warp::reject::custom(ApiError::bad_request(format!(
+            "Deployment {} is already at revision {}",
+            name, current_revision
+        ))));
+    }
+
+    let mut template = target
+        .spec
+        .as_ref()
+        .and_then(|s| s.template.clone())
+        .ok_or_else(|| warp::reject::custom(ApiError::internal("ReplicaSet has no pod template")))?;
+    if let Some(labels) = template.metadata.as_mut().and_then(|m| m.labels.as_mut()) {
+        labels.remove("pod-template-hash");
+    }
+    if let Some(spec) = dep.spec.as_mut() {
+        spec.template = template;
     }
+
+    // Replacing with the fetched resourceVersion fails with 409 if the
+    // deployment changed in the meantime.
+    let dep = deployments
+        .replace(&name, &PostParams { field_manager: Some(FIELD_MANAGER.to_string()), ..Default::default() }, &dep)
+        .await
+        .map_err(reject)?;
+
+    Ok(warp::reply::json(&deployment_info(&dep)))
 }
 
-pub async fn get_configmaps(namespace: String, client: Client) -> Result<impl warp::Reply, Infallible> {
-    let configmaps: Api<ConfigMap> = Api::namespaced(client, &namespace);
-    
-    match configmaps.list(&Default::default()).await {
-        Ok(cm_list) => {
-            let cm_info: Vec<ConfigMapInfo> = cm_list
-                .items
-                .into_iter()
-                .map(|cm| {
-                    let data_keys = cm.data
-                        .as_ref()
-                        .map(|d| d.keys().cloned().collect::<Vec<_>>())
-                        .unwrap_or_default();
+async fn owned_replica_sets(dep: &Deployment, replica_sets: &Api<ReplicaSet>) -> Result<Vec<ReplicaSet>, kube::Error> {
+    let selector = dep
+        .spec
+        .as_ref()
+        .map(|s| format_selector(&s.selector))
+        .unwrap_or_default();
+    let uid = dep.uid().unwrap_or_default();
 
-                    ConfigMapInfo {
-                        name: cm.name_any(),
-                        namespace: cm.namespace().unwrap_or_default(),
-                        data_keys,
-                        created_at: cm.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                        labels: cm.labels().clone(),
-                    }
-                })
-                .collect();
-            
-            Ok(warp::reply::json(&cm_info))
+    let list = replica_sets.list(&ListParams::default().labels(&selector)).await?;
+    Ok(list
+        .items
+        .into_iter()
+        .filter(|rs| rs.owner_references().iter().any(|owner| owner.uid == uid))
+        .collect())
+}
+
+fn revision_of(annotations: &BTreeMap<String, String>) -> Option<i64> {
+    annotations.get(REVISION_ANNOTATION).and_then(|r| r.parse().ok())
+}
+
+fn patch_params() -> PatchParams {
+    PatchParams {
+        field_manager: Some(FIELD_MANAGER.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Lists nodes with pod counts and usage. Given `server_cache`, the context's cache
+/// under the server's own credentials, pods are counted from its shared watch;
+/// otherwise they are listed as the caller, so no per-user cluster-wide pod watch
+/// is started and a caller who can't list pods gets no counts.
+pub async fn get_nodes(cache: ResourceCache, server_cache: Option<ResourceCache>) -> Result<impl warp::Reply, Rejection> {
+    let (mut node_info, status) = match cache.list(ResourceKind::Nodes, None) {
+        Some(cached) => (from_values::<NodeInfo>(cached.objects), cached.status),
+        None => {
+            let nodes: Api<Node> = Api::all(cache.client());
+            let node_list = nodes.list(&Default::default()).await.map_err(reject)?;
+            (node_list.items.iter().map(node_info).collect(), CacheStatus::Miss)
         }
-        Err(e) => {
-            eprintln!("Error fetching configmaps: {}", e);
-            Ok(warp::reply::json(&Vec::<ConfigMapInfo>::new()))
+    };
+
+    let counts = match &server_cache {
+        Some(server_cache) => node_pod_counts(server_cache, true).await,
+        None => node_pod_counts(&cache, false).await,
+    };
+    let usage = cache.usage().nodes(cache.client()).await;
+    for node in &mut node_info {
+        node.pod_count = counts
+            .as_ref()
+            .map(|counts| counts.get(&node.name).copied().unwrap_or(0));
+        node.usage = usage.as_ref().and_then(|usage| usage.get(&node.name).cloned());
+    }
+
+    Ok(cached_json(&node_info, status))
+}
+
+/// Counts pods per node, from the cache's pod watch when `watch` is set, or `None`
+/// when the pods can't be listed.
+async fn node_pod_counts(cache: &ResourceCache, watch: bool) -> Option<BTreeMap<String, usize>> {
+    let cached = if watch { cache.list(ResourceKind::Pods, None) } else { None };
+    let pods = match cached {
+        Some(cached) => from_values::<PodInfo>(cached.objects),
+        None => {
+            let pods: Api<Pod> = Api::all(cache.client());
+            match pods.list(&Default::default()).await {
+                Ok(list) => list.items.iter().map(pod_info).collect(),
+                Err(e) => {
+                    eprintln!("Failed to count pods per node: {}", e);
+                    return None;
+                }
+            }
         }
+    };
+
+    let mut counts = BTreeMap::new();
+    for node in pods.into_iter().filter_map(|pod| pod.node_name) {
+        *counts.entry(node).or_default() += 1;
     }
+    Some(counts)
 }
 
-pub async fn get_network_policies(namespace: String, client: Client) -> Result<impl warp::Reply, Infallible> {
-    let network_policies: Api<NetworkPolicy> = Api::namespaced(client, &namespace);
-    
-    match network_policies.list(&Default::default()).await {
-        Ok(np_list) => {
-            let np_info: Vec<NetworkPolicyInfo> = np_list
-                .items
-                .into_iter()
-                .map(|np| {
-                    let spec = np.spec.as_ref();
-                    
-                    NetworkPolicyInfo {
-                        name: np.name_any(),
-                        namespace: np.namespace().unwrap_or_default(),
-                        pod_selector: spec
-                            .and_then(|s| s.pod_selector.match_labels.as_ref())
-                            .cloned()
-                            .unwrap_or_default(),
-                        ingress_rules: spec
-                            .and_then(|s| s.ingress.as_ref())
-                            .map(|i| i.len())
-                            .unwrap_or(0),
-                        egress_rules: spec
-                            .and_then(|s| s.egress.as_ref())
-                            .map(|e| e.len())
-                            .unwrap_or(0),
-                        created_at: np.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-                        labels: np.labels().clone(),
-                    }
+pub async fn get_node(name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let nodes: Api<Node> = Api::all(client.clone());
+    let node = nodes.get(&name).await.map_err(reject)?;
+
+    let pods: Api<Pod> = Api::all(client.clone());
+    let pod_list = pods
+        .list(&ListParams::default().fields(&format!("spec.nodeName={}", name)))
+        .await
+        .map_err(reject)?;
+    let mut pods: Vec<PodInfo> = pod_list.items.iter().map(pod_info).collect();
+    attach_pod_usage(&cache, None, &mut pods).await;
+
+    let status = node.status.as_ref();
+    let system = status.and_then(|s| s.node_info.as_ref());
+    let mut info = node_info(&node);
+    info.pod_count = Some(pods.len());
+    info.usage = cache.usage().nodes(client).await.and_then(|usage| usage.get(&name).cloned());
+
+    let details = NodeDetails {
+        node: info,
+        annotations: node.annotations().clone(),
+        addresses: status
+            .and_then(|s| s.addresses.as_ref())
+            .map(|addresses| addresses.iter()
+                .map(|a| NodeAddressInfo {
+                    address_type: a.type_.clone(),
+                    address: a.address.clone(),
                 })
-                .collect();
-            
-            Ok(warp::reply::json(&np_info))
+                .collect())
+            .unwrap_or_default(),
+        os_image: system.map(|s| s.os_image.clone()).unwrap_or_default(),
+        kernel_version: system.map(|s| s.kernel_version.clone()).unwrap_or_default(),
+        container_runtime: system.map(|s| s.container_runtime_version.clone()).unwrap_or_default(),
+        pod_cidrs: node.spec.as_ref()
+            .and_then(|s| s.pod_cidrs.clone().or_else(|| s.pod_cidr.clone().map(|cidr| vec![cidr])))
+            .unwrap_or_default(),
+        pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+pub async fn cordon_node(name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let node = set_unschedulable(&name, true, client).await.map_err(reject)?;
+    Ok(warp::reply::json(&node_info(&node)))
+}
+
+pub async fn uncordon_node(name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let node = set_unschedulable(&name, false, client).await.map_err(reject)?;
+    Ok(warp::reply::json(&node_info(&node)))
+}
+
+pub async fn set_unschedulable(name: &str, unschedulable: bool, client: Client) -> Result<Node, kube::Error> {
+    let nodes: Api<Node> = Api::all(client);
+    let patch = serde_json::json!({ "spec": { "unschedulable": unschedulable } });
+    nodes.patch(name, &patch_params(), &Patch::Strategic(&patch)).await
+}
+
+/// Turns cached JSON objects back into their info types, skipping any that don't fit.
+pub(crate) fn from_values<T: DeserializeOwned>(values: Vec<serde_json::Value>) -> Vec<T> {
+    values.into_iter().filter_map(|v| serde_json::from_value(v).ok()).collect()
+}
+
+pub async fn get_replica_sets(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::ReplicaSets, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let replica_sets: Api<ReplicaSet> = Api::namespaced(cache.client(), &namespace);
+    let list = replica_sets.list(&Default::default()).await.map_err(reject)?;
+    let info: Vec<ReplicaSetInfo> = list.items.iter().map(replica_set_info).collect();
+
+    Ok(cached_json(&info, CacheStatus::Miss))
+}
+
+pub async fn get_replica_set(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let replica_sets: Api<ReplicaSet> = Api::namespaced(client.clone(), &namespace);
+    let rs = replica_sets.get(&name).await.map_err(reject)?;
+    let spec = rs.spec.as_ref();
+    let selector = spec.map(|s| format_selector(&s.selector)).unwrap_or_default();
+
+    let pods = controlled_pods(&cache, &namespace, &selector, &rs.uid().unwrap_or_default())
+        .await
+        .map_err(reject)?;
+    let details = ReplicaSetDetails {
+        replica_set: ReplicaSetInfo {
+            pod_count: Some(pods.len()),
+            ..replica_set_info(&rs)
+        },
+        annotations: rs.annotations().clone(),
+        selector,
+        conditions: conditions_of(rs.status.as_ref().and_then(|s| s.conditions.as_ref())),
+        containers: containers_of(spec.and_then(|s| s.template.as_ref())),
+        pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+pub async fn get_stateful_sets(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::StatefulSets, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let stateful_sets: Api<StatefulSet> = Api::namespaced(cache.client(), &namespace);
+    let list = stateful_sets.list(&Default::default()).await.map_err(reject)?;
+    let info: Vec<StatefulSetInfo> = list.items.iter().map(stateful_set_info).collect();
+
+    Ok(cached_json(&info, CacheStatus::Miss))
+}
+
+/// Returns a StatefulSet with the state of each ordinal: whether its pod exists,
+/// is ready and runs the update revision.
+pub async fn get_stateful_set(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let stateful_sets: Api<StatefulSet> = Api::namespaced(client.clone(), &namespace);
+    let sts = stateful_sets.get(&name).await.map_err(reject)?;
+    let spec = sts.spec.as_ref();
+    let status = sts.status.as_ref();
+    let selector = spec.map(|s| format_selector(&s.selector)).unwrap_or_default();
+    let update_revision = status.and_then(|s| s.update_revision.clone());
+
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+    let uid = sts.uid().unwrap_or_default();
+    let owned: Vec<Pod> = pods
+        .list(&ListParams::default().labels(&selector))
+        .await
+        .map_err(reject)?
+        .items
+        .into_iter()
+        .filter(|pod| is_controlled_by(pod, &uid))
+        .collect();
+
+    let by_ordinal: BTreeMap<i32, &Pod> = owned
+        .iter()
+        .filter_map(|pod| {
+            let ordinal = pod.name_any().strip_prefix(&format!("{}-", name))?.parse().ok()?;
+            Some((ordinal, pod))
+        })
+        .collect();
+    let start = spec
+        .and_then(|s| s.ordinals.as_ref())
+        .and_then(|o| o.start)
+        .unwrap_or(0);
+    let replicas = spec.and_then(|s| s.replicas).unwrap_or(1);
+    let ordinals: BTreeSet<i32> = (start..start + replicas).chain(by_ordinal.keys().copied()).collect();
+    let ordinals = ordinals
+        .into_iter()
+        .map(|ordinal| {
+            let pod = by_ordinal.get(&ordinal);
+            let revision = pod.and_then(|p| p.labels().get("controller-revision-hash").cloned());
+            OrdinalStatus {
+                ordinal,
+                pod_name: format!("{}-{}", name, ordinal),
+                phase: pod.and_then(|p| p.status.as_ref()).and_then(|s| s.phase.clone()),
+                ready: pod.map(|p| is_pod_ready(p)).unwrap_or(false),
+                updated: revision.is_some() && revision == update_revision,
+                revision,
+            }
+        })
+        .collect();
+
+    let mut pod_info: Vec<PodInfo> = owned.iter().map(pod_info).collect();
+    attach_pod_usage(&cache, Some(&namespace), &mut pod_info).await;
+
+    let rolling = spec
+        .and_then(|s| s.update_strategy.as_ref())
+        .and_then(|s| s.rolling_update.as_ref());
+    let details = StatefulSetDetails {
+        stateful_set: stateful_set_info(&sts),
+        annotations: sts.annotations().clone(),
+        selector,
+        update_strategy: spec
+            .and_then(|s| s.update_strategy.as_ref())
+            .and_then(|s| s.type_.clone())
+            .unwrap_or_else(|| "RollingUpdate".to_string()),
+        partition: rolling.and_then(|r| r.partition),
+        pod_management_policy: spec
+            .and_then(|s| s.pod_management_policy.clone())
+            .unwrap_or_else(|| "OrderedReady".to_string()),
+        current_revision: status.and_then(|s| s.current_revision.clone()),
+        update_revision,
+        volume_claim_templates: spec
+            .and_then(|s| s.volume_claim_templates.as_ref())
+            .map(|templates| templates.iter().map(|t| t.name_any()).collect())
+            .unwrap_or_default(),
+        conditions: conditions_of(status.and_then(|s| s.conditions.as_ref())),
+        containers: containers_of(spec.map(|s| &s.template)),
+        ordinals,
+        pods: pod_info,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+pub async fn get_daemon_sets(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::DaemonSets, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let daemon_sets: Api<DaemonSet> = Api::namespaced(cache.client(), &namespace);
+    let list = daemon_sets.list(&Default::default()).await.map_err(reject)?;
+    let info: Vec<DaemonSetInfo> = list.items.iter().map(daemon_set_info).collect();
+
+    Ok(cached_json(&info, CacheStatus::Miss))
+}
+
+pub async fn get_daemon_set(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let daemon_sets: Api<DaemonSet> = Api::namespaced(client.clone(), &namespace);
+    let ds = daemon_sets.get(&name).await.map_err(reject)?;
+    let spec = ds.spec.as_ref();
+    let selector = spec.map(|s| format_selector(&s.selector)).unwrap_or_default();
+
+    let pods = controlled_pods(&cache, &namespace, &selector, &ds.uid().unwrap_or_default())
+        .await
+        .map_err(reject)?;
+    let strategy = spec.and_then(|s| s.update_strategy.as_ref());
+    let details = DaemonSetDetails {
+        daemon_set: daemon_set_info(&ds),
+        annotations: ds.annotations().clone(),
+        selector,
+        update_strategy: strategy
+            .and_then(|s| s.type_.clone())
+            .unwrap_or_else(|| "RollingUpdate".to_string()),
+        max_unavailable: strategy
+            .and_then(|s| s.rolling_update.as_ref())
+            .and_then(|r| r.max_unavailable.as_ref())
+            .map(int_or_string),
+        tolerations: spec
+            .and_then(|s| s.template.spec.as_ref())
+            .and_then(|s| s.tolerations.as_ref())
+            .map(|tolerations| tolerations.iter().map(format_toleration).collect())
+            .unwrap_or_default(),
+        conditions: conditions_of(ds.status.as_ref().and_then(|s| s.conditions.as_ref())),
+        containers: containers_of(spec.map(|s| &s.template)),
+        pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+fn format_toleration(toleration: &Toleration) -> String {
+    let key = toleration.key.as_deref().unwrap_or("*");
+    let mut formatted = match (toleration.operator.as_deref(), &toleration.value) {
+        (Some("Exists"), _) | (_, None) => key.to_string(),
+        (_, Some(value)) => format!("{}={}", key, value),
+    };
+    if let Some(effect) = &toleration.effect {
+        formatted.push(':');
+        formatted.push_str(effect);
+    }
+    formatted
+}
+
+pub async fn get_jobs(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::Jobs, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let jobs: Api<Job> = Api::namespaced(cache.client(), &namespace);
+    let list = jobs.list(&Default::default()).await.map_err(reject)?;
+    let info: Vec<JobInfo> = list.items.iter().map(job_info).collect();
+
+    Ok(cached_json(&info, CacheStatus::Miss))
+}
+
+pub async fn get_job(namespace: String, name: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let jobs: Api<Job> = Api::namespaced(client.clone(), &namespace);
+    let job = jobs.get(&name).await.map_err(reject)?;
+    let spec = job.spec.as_ref();
+    let selector = spec
+        .and_then(|s| s.selector.as_ref())
+        .map(format_selector)
+        .unwrap_or_default();
+
+    let pods = controlled_pods(&cache, &namespace, &selector, &job.uid().unwrap_or_default())
+        .await
+        .map_err(reject)?;
+    let details = JobDetails {
+        job: job_info(&job),
+        annotations: job.annotations().clone(),
+        backoff_limit: spec.and_then(|s| s.backoff_limit),
+        active_deadline_seconds: spec.and_then(|s| s.active_deadline_seconds),
+        ttl_seconds_after_finished: spec.and_then(|s| s.ttl_seconds_after_finished),
+        conditions: conditions_of(job.status.as_ref().and_then(|s| s.conditions.as_ref())),
+        containers: containers_of(spec.map(|s| &s.template)),
+        pods,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+pub async fn get_cron_jobs(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::CronJobs, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let cron_jobs: Api<CronJob> = Api::namespaced(cache.client(), &namespace);
+    let list = cron_jobs.list(&Default::default()).await.map_err(reject)?;
+    let info: Vec<CronJobInfo> = list.items.iter().map(cron_job_info).collect();
+
+    Ok(cached_json(&info, CacheStatus::Miss))
+}
+
+/// Returns a CronJob with the jobs it spawned and their pods.
+pub async fn get_cron_job(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let cron_jobs: Api<CronJob> = Api::namespaced(client.clone(), &namespace);
+    let cron_job = cron_jobs.get(&name).await.map_err(reject)?;
+    let spec = cron_job.spec.as_ref();
+    let jobs = cron_job_runs(&cron_job, &namespace, client).await.map_err(reject)?;
+
+    let details = CronJobDetails {
+        cron_job: cron_job_info(&cron_job),
+        annotations: cron_job.annotations().clone(),
+        concurrency_policy: spec
+            .and_then(|s| s.concurrency_policy.clone())
+            .unwrap_or_else(|| "Allow".to_string()),
+        starting_deadline_seconds: spec.and_then(|s| s.starting_deadline_seconds),
+        successful_jobs_history_limit: spec.and_then(|s| s.successful_jobs_history_limit),
+        failed_jobs_history_limit: spec.and_then(|s| s.failed_jobs_history_limit),
+        active_jobs: cron_job
+            .status
+            .as_ref()
+            .and_then(|s| s.active.as_ref())
+            .map(|refs| refs.iter().filter_map(|r| r.name.clone()).collect())
+            .unwrap_or_default(),
+        containers: containers_of(
+            spec.and_then(|s| s.job_template.spec.as_ref())
+                .map(|s| &s.template),
+        ),
+        jobs,
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+async fn cron_job_runs(cron_job: &CronJob, namespace: &str, client: Client) -> Result<Vec<CronJobRun>, kube::Error> {
+    let jobs: Api<Job> = Api::namespaced(client.clone(), namespace);
+    let pods: Api<Pod> = Api::namespaced(client, namespace);
+    let uid = cron_job.uid().unwrap_or_default();
+
+    let mut owned: Vec<Job> = jobs
+        .list(&Default::default())
+        .await?
+        .items
+        .into_iter()
+        .filter(|job| is_controlled_by(job, &uid))
+        .collect();
+    owned.sort_by_key(|job| std::cmp::Reverse(job.creation_timestamp()));
+    // Job pods carry a job-name label, which narrows the list before matching owners
+    let job_pods = pods.list(&ListParams::default().labels("job-name")).await?.items;
+
+    Ok(owned
+        .iter()
+        .map(|job| {
+            let job_uid = job.uid().unwrap_or_default();
+            CronJobRun {
+                job: job_info(job),
+                manual: job.annotations().get(CRONJOB_INSTANTIATE_ANNOTATION).map(String::as_str) == Some("manual"),
+                pods: job_pods
+                    .iter()
+                    .filter(|pod| is_controlled_by(*pod, &job_uid))
+                    .map(pod_info)
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+/// Starts a Job from the CronJob's job template, like `kubectl create job --from=cronjob/<name>`.
+pub async fn trigger_cron_job(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let cron_jobs: Api<CronJob> = Api::namespaced(client.clone(), &namespace);
+    let jobs: Api<Job> = Api::namespaced(client, &namespace);
+
+    let cron_job = cron_jobs.get(&name).await.map_err(reject)?;
+    let template = cron_job
+        .spec
+        .as_ref()
+        .map(|s| s.job_template.clone())
+        .ok_or_else(|| warp::reject::custom(ApiError::internal("CronJob has no job template")))?;
+    let template_meta = template.metadata.unwrap_or_default();
+
+    let mut annotations = template_meta.annotations.unwrap_or_default();
+    annotations.insert(CRONJOB_INSTANTIATE_ANNOTATION.to_string(), "manual".to_string());
+    // Job names end up in pod labels, so leave room for the generated suffix within 63 characters
+    let prefix: String = name.chars().take(50).collect();
+    let job = Job {
+        metadata: ObjectMeta {
+            generate_name: Some(format!("{}-manual-", prefix.trim_end_matches('-'))),
+            namespace: Some(namespace.clone()),
+            labels: template_meta.labels,
+            annotations: Some(annotations),
+            owner_references: cron_job.controller_owner_ref(&()).map(|owner| vec![owner]),
+            ..Default::default()
+        },
+        spec: template.spec,
+        ..Default::default()
+    };
+
+    let params = PostParams {
+        field_manager: Some(FIELD_MANAGER.to_string()),
+        ..Default::default()
+    };
+    let job = jobs.create(&params, &job).await.map_err(reject)?;
+    Ok(warp::reply::with_status(warp::reply::json(&job_info(&job)), StatusCode::CREATED))
+}
+
+pub async fn suspend_cron_job(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    set_cron_job_suspended(namespace, name, true, client).await
+}
+
+pub async fn resume_cron_job(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    set_cron_job_suspended(namespace, name, false, client).await
+}
+
+async fn set_cron_job_suspended(namespace: String, name: String, suspend: bool, client: Client) -> Result<warp::reply::Json, Rejection> {
+    let cron_jobs: Api<CronJob> = Api::namespaced(client, &namespace);
+    let patch = serde_json::json!({ "spec": { "suspend": suspend } });
+    let cron_job = cron_jobs
+        .patch(&name, &patch_params(), &Patch::Merge(&patch))
+        .await
+        .map_err(reject)?;
+    Ok(warp::reply::json(&cron_job_info(&cron_job)))
+}
+
+/// Lists pods matching the selector whose controller has the given UID, with live usage.
+async fn controlled_pods(cache: &ResourceCache, namespace: &str, selector: &str, uid: &str) -> Result<Vec<PodInfo>, kube::Error> {
+    let pods: Api<Pod> = Api::namespaced(cache.client(), namespace);
+    let list = pods.list(&ListParams::default().labels(selector)).await?;
+    let mut info: Vec<PodInfo> = list
+        .items
+        .iter()
+        .filter(|pod| is_controlled_by(*pod, uid))
+        .map(pod_info)
+        .collect();
+    attach_pod_usage(cache, Some(namespace), &mut info).await;
+    Ok(info)
+}
+
+fn is_controlled_by<K: ResourceExt>(obj: &K, uid: &str) -> bool {
+    obj.owner_references()
+        .iter()
+        .any(|owner| owner.controller == Some(true) && owner.uid == uid)
+}
+
+/// Controlling owner as `Kind/name`.
+fn controller_of<K: ResourceExt>(obj: &K) -> Option<String> {
+    obj.owner_references()
+        .iter()
+        .find(|owner| owner.controller == Some(true))
+        .map(|owner| format!("{}/{}", owner.kind, owner.name))
+}
+
+fn is_pod_ready(pod: &Pod) -> bool {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .map(|cs| cs.iter().any(|c| c.type_ == "Ready" && c.status == "True"))
+        .unwrap_or(false)
+}
+
+async fn attach_pod_usage(cache: &ResourceCache, namespace: Option<&str>, pods: &mut [PodInfo]) {
+    if let Some(usage) = cache.usage().pods(cache.client(), namespace).await {
+        for pod in pods {
+            pod.usage = usage.get(&(pod.namespace.clone(), pod.name.clone())).map(|u| u.total.clone());
         }
-        Err(e) => {
-            eprintln!("Error fetching network policies: {}", e);
-            Ok(warp::reply::json(&Vec::<NetworkPolicyInfo>::new()))
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnyCondition {
+    #[serde(rename = "type")]
+    type_: String,
+    status: String,
+    reason: Option<String>,
+    message: Option<String>,
+    last_transition_time: Option<Time>,
+}
+
+/// Converts the conditions of any built-in kind; they all share the same core fields.
+fn conditions_of<C: serde::Serialize>(conditions: Option<&Vec<C>>) -> Vec<ConditionInfo> {
+    conditions
+        .into_iter()
+        .flatten()
+        .filter_map(|c| serde_json::to_value(c).ok())
+        .filter_map(|v| serde_json::from_value::<AnyCondition>(v).ok())
+        .map(|c| ConditionInfo {
+            condition_type: c.type_,
+            status: c.status,
+            reason: c.reason,
+            message: c.message,
+            last_transition_time: c.last_transition_time.map(|t| t.0.to_rfc3339()),
+        })
+        .collect()
+}
+
+fn containers_of(template: Option<&PodTemplateSpec>) -> Vec<ContainerImage> {
+    template
+        .and_then(|t| t.spec.as_ref())
+        .map(|spec| spec.containers.iter()
+            .map(|c| ContainerImage {
+                name: c.name.clone(),
+                image: c.image.clone().unwrap_or_default(),
+            })
+            .collect())
+        .unwrap_or_default()
+}
+
+pub async fn get_configmaps(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::ConfigMaps, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let configmaps: Api<ConfigMap> = Api::namespaced(cache.client(), &namespace);
+    
+    let cm_list = configmaps.list(&Default::default()).await.map_err(reject)?;
+
+    let cm_info: Vec<ConfigMapInfo> = cm_list.items.iter().map(configmap_info).collect();
+    
+    Ok(cached_json(&cm_info, CacheStatus::Miss))
+}
+
+pub async fn get_configmap(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let configmaps: Api<ConfigMap> = Api::namespaced(client, &namespace);
+
+    let cm = configmaps.get(&name).await.map_err(reject)?;
+
+    Ok(warp::reply::json(&configmap_details(&cm)))
+}
+
+/// Replaces a ConfigMap's data, keeping binaryData and metadata. The submitted
+/// resourceVersion is sent as a precondition, so an edit based on a stale copy
+/// gets a 409 with the current object and a per-key diff instead of overwriting it.
+pub async fn update_configmap(
+    namespace: String,
+    name: String,
+    update: ConfigMapUpdate,
+    client: Client,
+) -> Result<warp::reply::Response, Rejection> {
+    let configmaps: Api<ConfigMap> = Api::namespaced(client, &namespace);
+
+    let mut cm = configmaps.get(&name).await.map_err(reject)?;
+    if cm.resource_version().as_deref() != Some(update.resource_version.as_str()) {
+        return Ok(configmap_conflict(&cm, &update.data));
+    }
+
+    cm.metadata.managed_fields = None;
+    cm.data = Some(update.data.clone());
+    let params = PostParams {
+        field_manager: Some(FIELD_MANAGER.to_string()),
+        ..Default::default()
+    };
+    match configmaps.replace(&name, &params, &cm).await {
+        Ok(updated) => Ok(warp::reply::json(&configmap_details(&updated)).into_response()),
+        // Someone else wrote between our read and the replace.
+        Err(kube::Error::Api(resp)) if resp.code == 409 => {
+            let current = configmaps.get(&name).await.map_err(reject)?;
+            Ok(configmap_conflict(&current, &update.data))
         }
+        Err(e) => Err(reject(e)),
+    }
+}
+
+fn configmap_conflict(current: &ConfigMap, submitted: &BTreeMap<String, String>) -> warp::reply::Response {
+    let details = configmap_details(current);
+    let diff = configmap_diff(submitted, &details.data);
+    let conflict = ConfigMapConflict {
+        code: StatusCode::CONFLICT.as_u16(),
+        reason: "Conflict".to_string(),
+        message: format!(
+            "ConfigMap {} was modified by someone else (now at version {})",
+            details.name, details.resource_version
+        ),
+        current: details,
+        diff,
+    };
+    warp::reply::with_status(warp::reply::json(&conflict), StatusCode::CONFLICT).into_response()
+}
+
+fn configmap_diff(submitted: &BTreeMap<String, String>, current: &BTreeMap<String, String>) -> Vec<ConfigMapDataChange> {
+    let keys: BTreeSet<&String> = submitted.keys().chain(current.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (ours, theirs) = (submitted.get(key), current.get(key));
+            let change = match (ours, theirs) {
+                (Some(_), None) => "added",
+                (None, Some(_)) => "removed",
+                (Some(a), Some(b)) if a != b => "modified",
+                _ => return None,
+            };
+            Some(ConfigMapDataChange {
+                key: key.clone(),
+                change: change.to_string(),
+                submitted: ours.cloned(),
+                current: theirs.cloned(),
+            })
+        })
+        .collect()
+}
+
+pub async fn get_network_policies(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::NetworkPolicies, Some(&namespace)) {
+        return Ok(cached_json(&cached.objects, cached.status));
+    }
+
+    let network_policies: Api<NetworkPolicy> = Api::namespaced(cache.client(), &namespace);
+    
+    let np_list = network_policies.list(&Default::default()).await.map_err(reject)?;
+
+    let np_info: Vec<NetworkPolicyInfo> = np_list.items.iter().map(network_policy_info).collect();
+    
+    Ok(cached_json(&np_info, CacheStatus::Miss))
+}
+
+/// Lists Secrets with key names and sizes only. Secrets are deliberately not kept
+/// in the shared cache, so this always lists from the API server.
+pub async fn get_secrets(namespace: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let secrets: Api<Secret> = Api::namespaced(client, &namespace);
+
+    let secret_list = secrets.list(&Default::default()).await.map_err(reject)?;
+
+    let secret_info: Vec<SecretInfo> = secret_list.items.iter().map(secret_info).collect();
+
+    Ok(warp::reply::json(&secret_info))
+}
+
+/// Decodes a single Secret key. Disabled unless `allow_reveal` is configured, and
+/// every attempt is written to the audit log.
+pub async fn reveal_secret(
+    namespace: String,
+    name: String,
+    request: RevealSecretRequest,
+    settings: Settings,
+    audit: RequestAudit,
+    client: Client,
+) -> Result<impl warp::Reply, Rejection> {
+    let target = format!("secrets/{}/{}:{}", namespace, name, request.key);
+    let record = |outcome: &str| audit.record("secret.reveal", &target, outcome);
+
+    if !settings.secrets.allow_reveal {
+        record("denied: reveal disabled");
+        return Err(warp::reject::custom(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Forbidden",
+            "Revealing secret values is disabled on this server",
+        )));
     }
+
+    let secrets: Api<Secret> = Api::namespaced(client, &namespace);
+    let secret = secrets.get(&name).await.map_err(|e| {
+        record(&format!("error: {}", e));
+        reject(e)
+    })?;
+    let Some(bytes) = secret.data.as_ref().and_then(|data| data.get(&request.key)) else {
+        record("error: key not found");
+        return Err(warp::reject::custom(ApiError::not_found(format!(
+            "Secret {} has no key {}",
+            name, request.key
+        ))));
+    };
+    record("revealed");
+
+    let (encoding, value) = match std::str::from_utf8(&bytes.0) {
+        Ok(text) => ("utf-8", text.to_string()),
+        Err(_) => ("base64", STANDARD.encode(&bytes.0)),
+    };
+    Ok(warp::reply::json(&SecretValue {
+        name,
+        namespace,
+        key: request.key,
+        encoding: encoding.to_string(),
+        value,
+    }))
 }
 
-pub async fn get_pod_details(namespace: String, pod_name: String, client: Client) -> Result<impl warp::Reply, Infallible> {
+/// Lists Events for one namespace, or all namespaces when `namespace` is `None`.
+/// Filters are passed to the API server as field selectors; newest events come first.
+pub async fn get_events(namespace: Option<String>, query: EventQuery, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let events: Api<Event> = match &namespace {
+        Some(ns) => Api::namespaced(client, ns),
+        None => Api::all(client),
+    };
+
+    let mut fields = Vec::new();
+    if let Some(kind) = query.kind.as_deref().filter(|k| !k.is_empty()) {
+        fields.push(format!("involvedObject.kind={}", kind));
+    }
+    if let Some(name) = query.name.as_deref().filter(|n| !n.is_empty()) {
+        fields.push(format!("involvedObject.name={}", name));
+    }
+    if let Some(event_type) = query.event_type.as_deref().filter(|t| !t.is_empty()) {
+        fields.push(format!("type={}", event_type));
+    }
+    let mut params = ListParams::default();
+    if !fields.is_empty() {
+        params = params.fields(&fields.join(","));
+    }
+
+    let event_list = events.list(&params).await.map_err(reject)?;
+
+    let mut event_info: Vec<EventInfo> = event_list.items.iter().map(event_info).collect();
+    event_info.sort_by(|a, b| b.last_timestamp.cmp(&a.last_timestamp));
+
+    Ok(warp::reply::json(&event_info))
+}
+
+pub async fn delete_pod(namespace: String, pod_name: String, query: DeletePodQuery, client: Client) -> Result<impl warp::Reply, Rejection> {
     let pods: Api<Pod> = Api::namespaced(client, &namespace);
+
+    // Like `kubectl delete --force`, forcing implies an immediate (zero) grace period.
+    let params = DeleteParams {
+        grace_period_seconds: if query.force { Some(0) } else { query.grace_period_seconds },
+        ..Default::default()
+    };
+    let deleted = pods.delete(&pod_name, &params).await.map_err(reject)?;
+    let message = if deleted.is_left() { "Pod is terminating" } else { "Pod deleted" };
+
+    Ok(warp::reply::json(&PodActionResult {
+        name: pod_name,
+        namespace,
+        action: "delete".to_string(),
+        message: message.to_string(),
+    }))
+}
+
+pub async fn evict_pod(namespace: String, pod_name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+
+    request_eviction(&pods, &pod_name, None).await.map_err(warp::reject::custom)?;
+
+    Ok(warp::reply::json(&PodActionResult {
+        name: pod_name,
+        namespace,
+        action: "evict".to_string(),
+        message: "Eviction accepted".to_string(),
+    }))
+}
+
+/// Evicts a pod through the Eviction subresource so PodDisruptionBudgets apply.
+/// A budget refusal (429 from the API server) is reported as `DisruptionBudgetViolation`.
+///
+/// The Eviction body is built here rather than with `Api::evict`, whose `EvictParams`
+/// serializes `deleteOptions` under the wrong key so a grace period would be ignored.
+pub async fn request_eviction(pods: &Api<Pod>, pod_name: &str, grace_period_seconds: Option<u32>) -> Result<(), ApiError> {
+    let eviction = Eviction {
+        metadata: ObjectMeta {
+            name: Some(pod_name.to_string()),
+            ..Default::default()
+        },
+        delete_options: grace_period_seconds.map(|grace| DeleteOptions {
+            grace_period_seconds: Some(grace.into()),
+            ..Default::default()
+        }),
+    };
+    let body = serde_json::to_vec(&eviction).map_err(|e| ApiError::internal(e.to_string()))?;
+    match pods
+        .create_subresource::<serde_json::Value>("eviction", pod_name, &PostParams::default(), body)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(resp)) if resp.code == 429 => {
+            let mut error = ApiError::from(kube::Error::Api(resp));
+            error.reason = "DisruptionBudgetViolation".to_string();
+            Err(error)
+        }
+        Err(e) => Err(ApiError::from(e)),
+    }
+}
+
+pub fn namespace_info(ns: &Namespace) -> NamespaceInfo {
+    NamespaceInfo {
+        name: ns.name_any(),
+        status: ns.status.as_ref().and_then(|s| s.phase.as_ref()).unwrap_or(&String::new()).clone(),
+        created_at: ns.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: ns.labels().clone(),
+    }
+}
+
+pub fn pod_info(pod: &Pod) -> PodInfo {
+    let status = pod.status.as_ref();
+    let phase = status
+        .and_then(|s| s.phase.as_ref())
+        .unwrap_or(&"Unknown".to_string())
+        .clone();
+    
+    let ready_containers = status
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(|cs| cs.iter().filter(|c| c.ready).count())
+        .unwrap_or(0);
     
-    match pods.get(&pod_name).await {
-        Ok(pod) => {
-            let details = PodDetails {
-                name: pod.name_any(),
-                namespace: pod.namespace().unwrap_or_default(),
-                labels: pod.labels().clone(),
-                annotations: pod.annotations().clone(),
-                node_name: pod.spec.as_ref()
-                    .and_then(|s| s.node_name.clone())
-                    .unwrap_or_default(),
-                status: pod.status.as_ref()
-                    .and_then(|s| s.phase.clone())
-                    .unwrap_or_default(),
-                containers: pod.spec.as_ref()
-                    .map(|s| s.containers.iter()
-                        .map(|c| ContainerInfo {
-                            name: c.name.clone(),
-                            image: c.image.clone().unwrap_or_default(),
-                            ports: c.ports.as_ref()
-                                .map(|ports| ports.iter()
-                                    .map(|p| format!("{}:{}", p.container_port, p.protocol.as_ref().unwrap_or(&"TCP".to_string())))
-                                    .collect())
-                                .unwrap_or_default(),
-                            resources: format!(
-                                "CPU: {} / MEM: {}",
-                                c.resources.as_ref()
-                                    .and_then(|r| r.requests.as_ref())
-                                    .and_then(|req| req.get("cpu"))
-                                    .map(|v| v.0.clone())
-                                    .unwrap_or_default(),
-                                c.resources.as_ref()
-                                    .and_then(|r| r.requests.as_ref())
-                                    .and_then(|req| req.get("memory"))
-                                    .map(|v| v.0.clone())
-                                    .unwrap_or_default()
-                            ),
+    let total_containers = status
+        .and_then(|s| s.container_statuses.as_ref())
+        .map(|cs| cs.len())
+        .unwrap_or(0);
+
+    PodInfo {
+        name: pod.name_any(),
+        namespace: pod.namespace().unwrap_or_default(),
+        phase,
+        ready: format!("{}/{}", ready_containers, total_containers),
+        restarts: status
+            .and_then(|s| s.container_statuses.as_ref())
+            .map(|cs| cs.iter().map(|c| c.restart_count).sum::<i32>())
+            .unwrap_or(0),
+        created_at: pod.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        node_name: pod.spec.as_ref().and_then(|s| s.node_name.clone()),
+        host_ip: status.and_then(|s| s.host_ip.clone()),
+        labels: pod.labels().clone(),
+        requests: pod_amounts(pod, |r| r.requests.as_ref()),
+        limits: pod_amounts(pod, |r| r.limits.as_ref()),
+        usage: None,
+    }
+}
+
+fn pod_amounts(pod: &Pod, select: fn(&ResourceRequirements) -> Option<&BTreeMap<String, Quantity>>) -> ResourceAmounts {
+    let containers: Vec<ResourceAmounts> = pod.spec.iter()
+        .flat_map(|s| s.containers.iter())
+        .map(|c| container_amounts(c, select))
+        .collect();
+    metrics::sum(&containers)
+}
+
+fn container_amounts(container: &Container, select: fn(&ResourceRequirements) -> Option<&BTreeMap<String, Quantity>>) -> ResourceAmounts {
+    container.resources.as_ref()
+        .and_then(select)
+        .map(metrics::amounts)
+        .unwrap_or_default()
+}
+
+pub fn node_info(node: &Node) -> NodeInfo {
+    let status = node.status.as_ref();
+    let system = status.and_then(|s| s.node_info.as_ref());
+    let conditions = status.and_then(|s| s.conditions.as_ref());
+
+    let mut roles: Vec<String> = node.labels()
+        .iter()
+        .filter_map(|(key, value)| match key.strip_prefix("node-role.kubernetes.io/") {
+            Some(role) if !role.is_empty() => Some(role.to_string()),
+            _ if key == "kubernetes.io/role" => Some(value.clone()),
+            _ => None,
+        })
+        .collect();
+    roles.sort();
+    roles.dedup();
+
+    let quantities = |map: Option<&BTreeMap<String, Quantity>>| {
+        map.map(|m| m.iter().map(|(k, v)| (k.clone(), v.0.clone())).collect())
+            .unwrap_or_default()
+    };
+
+    NodeInfo {
+        name: node.name_any(),
+        roles,
+        status: conditions
+            .and_then(|cs| cs.iter().find(|c| c.type_ == "Ready"))
+            .map(|c| match c.status.as_str() {
+                "True" => "Ready",
+                "False" => "NotReady",
+                _ => "Unknown",
+            })
+            .unwrap_or("Unknown")
+            .to_string(),
+        unschedulable: node.spec.as_ref().and_then(|s| s.unschedulable).unwrap_or(false),
+        kubelet_version: system.map(|s| s.kubelet_version.clone()).unwrap_or_default(),
+        os: system.map(|s| s.operating_system.clone()).unwrap_or_default(),
+        arch: system.map(|s| s.architecture.clone()).unwrap_or_default(),
+        internal_ip: status
+            .and_then(|s| s.addresses.as_ref())
+            .and_then(|addresses| addresses.iter().find(|a| a.type_ == "InternalIP"))
+            .map(|a| a.address.clone()),
+        conditions: conditions_of(conditions),
+        taints: node.spec.as_ref()
+            .and_then(|s| s.taints.as_ref())
+            .map(|taints| taints.iter()
+                .map(|t| TaintInfo {
+                    key: t.key.clone(),
+                    value: t.value.clone(),
+                    effect: t.effect.clone(),
+                })
+                .collect())
+            .unwrap_or_default(),
+        capacity: quantities(status.and_then(|s| s.capacity.as_ref())),
+        allocatable: quantities(status.and_then(|s| s.allocatable.as_ref())),
+        pod_count: None,
+        usage: None,
+        created_at: node.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: node.labels().clone(),
+    }
+}
+
+pub fn service_info(svc: &Service) -> ServiceInfo {
+    let spec = svc.spec.as_ref();
+    let ports = spec
+        .and_then(|s| s.ports.as_ref())
+        .map(|ports| {
+            ports
+                .iter()
+                .map(|p| {
+                    let target_port = p.target_port.as_ref()
+                        .map(|tp| match tp {
+                            k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::Int(i) => i.to_string(),
+                            k8s_openapi::apimachinery::pkg::util::intstr::IntOrString::String(s) => s.clone(),
                         })
-                        .collect())
-                    .unwrap_or_default(),
-                created_at: pod.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
-            };
-            
-            Ok(warp::reply::json(&details))
-        }
-        Err(e) => {
-            eprintln!("Error fetching pod details: {}", e);
-            Ok(warp::reply::json(&serde_json::json!({"error": "Pod not found"})))
-        }
+                        .unwrap_or_default();
+                    format!("{}:{}", p.port, target_port)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+
+    ServiceInfo {
+        name: svc.name_any(),
+        namespace: svc.namespace().unwrap_or_default(),
+        service_type: spec
+            .and_then(|s| s.type_.as_ref())
+            .unwrap_or(&"ClusterIP".to_string())
+            .clone(),
+        cluster_ip: spec
+            .and_then(|s| s.cluster_ip.as_ref())
+            .unwrap_or(&"None".to_string())
+            .clone(),
+        external_ip: spec
+            .and_then(|s| s.external_ips.as_ref())
+            .and_then(|ips| ips.first())
+            .unwrap_or(&"<none>".to_string())
+            .clone(),
+        ports,
+        created_at: svc.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: svc.labels().clone(),
+    }
+}
+
+pub fn deployment_info(dep: &Deployment) -> DeploymentInfo {
+    let status = dep.status.as_ref();
+    let spec = dep.spec.as_ref();
+
+    DeploymentInfo {
+        name: dep.name_any(),
+        namespace: dep.namespace().unwrap_or_default(),
+        ready_replicas: status
+            .and_then(|s| s.ready_replicas)
+            .unwrap_or(0),
+        replicas: spec
+            .and_then(|s| s.replicas)
+            .unwrap_or(0),
+        updated_replicas: status
+            .and_then(|s| s.updated_replicas)
+            .unwrap_or(0),
+        available_replicas: status
+            .and_then(|s| s.available_replicas)
+            .unwrap_or(0),
+        created_at: dep.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: dep.labels().clone(),
+    }
+}
+
+pub fn replica_set_info(rs: &ReplicaSet) -> ReplicaSetInfo {
+    let status = rs.status.as_ref();
+
+    ReplicaSetInfo {
+        name: rs.name_any(),
+        namespace: rs.namespace().unwrap_or_default(),
+        revision: revision_of(rs.annotations()),
+        replicas: rs.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0),
+        ready_replicas: status.and_then(|s| s.ready_replicas).unwrap_or(0),
+        available_replicas: status.and_then(|s| s.available_replicas).unwrap_or(0),
+        images: rs
+            .spec
+            .as_ref()
+            .and_then(|s| s.template.as_ref())
+            .and_then(|t| t.spec.as_ref())
+            .map(|pod_spec| pod_spec.containers.iter().filter_map(|c| c.image.clone()).collect())
+            .unwrap_or_default(),
+        pod_count: None,
+        owner: controller_of(rs),
+        created_at: rs.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: rs.labels().clone(),
+    }
+}
+
+pub fn stateful_set_info(sts: &StatefulSet) -> StatefulSetInfo {
+    let status = sts.status.as_ref();
+    let spec = sts.spec.as_ref();
+
+    StatefulSetInfo {
+        name: sts.name_any(),
+        namespace: sts.namespace().unwrap_or_default(),
+        replicas: spec.and_then(|s| s.replicas).unwrap_or(1),
+        ready_replicas: status.and_then(|s| s.ready_replicas).unwrap_or(0),
+        current_replicas: status.and_then(|s| s.current_replicas).unwrap_or(0),
+        updated_replicas: status.and_then(|s| s.updated_replicas).unwrap_or(0),
+        service_name: spec.map(|s| s.service_name.clone()).unwrap_or_default(),
+        created_at: sts.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: sts.labels().clone(),
+    }
+}
+
+pub fn daemon_set_info(ds: &DaemonSet) -> DaemonSetInfo {
+    let status = ds.status.as_ref();
+
+    DaemonSetInfo {
+        name: ds.name_any(),
+        namespace: ds.namespace().unwrap_or_default(),
+        desired: status.map(|s| s.desired_number_scheduled).unwrap_or(0),
+        current: status.map(|s| s.current_number_scheduled).unwrap_or(0),
+        ready: status.map(|s| s.number_ready).unwrap_or(0),
+        up_to_date: status.and_then(|s| s.updated_number_scheduled).unwrap_or(0),
+        available: status.and_then(|s| s.number_available).unwrap_or(0),
+        misscheduled: status.map(|s| s.number_misscheduled).unwrap_or(0),
+        node_selector: ds
+            .spec
+            .as_ref()
+            .and_then(|s| s.template.spec.as_ref())
+            .and_then(|s| s.node_selector.clone())
+            .unwrap_or_default(),
+        created_at: ds.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: ds.labels().clone(),
+    }
+}
+
+pub fn job_info(job: &Job) -> JobInfo {
+    let status = job.status.as_ref();
+    let spec = job.spec.as_ref();
+    let condition = |type_: &str| {
+        status
+            .and_then(|s| s.conditions.as_ref())
+            .map(|cs| cs.iter().any(|c| c.type_ == type_ && c.status == "True"))
+            .unwrap_or(false)
+    };
+    let state = if condition("Complete") {
+        "Complete"
+    } else if condition("Failed") {
+        "Failed"
+    } else if spec.and_then(|s| s.suspend).unwrap_or(false) {
+        "Suspended"
+    } else {
+        "Running"
+    };
+
+    JobInfo {
+        name: job.name_any(),
+        namespace: job.namespace().unwrap_or_default(),
+        status: state.to_string(),
+        completions: spec.and_then(|s| s.completions),
+        parallelism: spec.and_then(|s| s.parallelism).unwrap_or(1),
+        active: status.and_then(|s| s.active).unwrap_or(0),
+        succeeded: status.and_then(|s| s.succeeded).unwrap_or(0),
+        failed: status.and_then(|s| s.failed).unwrap_or(0),
+        start_time: status.and_then(|s| s.start_time.as_ref()).map(|t| t.0.to_rfc3339()),
+        completion_time: status.and_then(|s| s.completion_time.as_ref()).map(|t| t.0.to_rfc3339()),
+        cron_job: job
+            .owner_references()
+            .iter()
+            .find(|owner| owner.kind == "CronJob" && owner.controller == Some(true))
+            .map(|owner| owner.name.clone()),
+        created_at: job.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: job.labels().clone(),
+    }
+}
+
+pub fn cron_job_info(cron_job: &CronJob) -> CronJobInfo {
+    let status = cron_job.status.as_ref();
+    let spec = cron_job.spec.as_ref();
+
+    CronJobInfo {
+        name: cron_job.name_any(),
+        namespace: cron_job.namespace().unwrap_or_default(),
+        schedule: spec.map(|s| s.schedule.clone()).unwrap_or_default(),
+        time_zone: spec.and_then(|s| s.time_zone.clone()),
+        suspend: spec.and_then(|s| s.suspend).unwrap_or(false),
+        active: status.and_then(|s| s.active.as_ref()).map(Vec::len).unwrap_or(0),
+        last_schedule_time: status.and_then(|s| s.last_schedule_time.as_ref()).map(|t| t.0.to_rfc3339()),
+        last_successful_time: status.and_then(|s| s.last_successful_time.as_ref()).map(|t| t.0.to_rfc3339()),
+        created_at: cron_job.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: cron_job.labels().clone(),
     }
 }
 
-pub async fn get_pod_logs(namespace: String, pod_name: String, client: Client) -> Result<impl warp::Reply, Infallible> {
+pub fn configmap_info(cm: &ConfigMap) -> ConfigMapInfo {
+    let data_keys = cm.data
+        .as_ref()
+        .map(|d| d.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    ConfigMapInfo {
+        name: cm.name_any(),
+        namespace: cm.namespace().unwrap_or_default(),
+        data_keys,
+        created_at: cm.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: cm.labels().clone(),
+    }
+}
+
+pub fn configmap_details(cm: &ConfigMap) -> ConfigMapDetails {
+    ConfigMapDetails {
+        name: cm.name_any(),
+        namespace: cm.namespace().unwrap_or_default(),
+        resource_version: cm.resource_version().unwrap_or_default(),
+        data: cm.data.clone().unwrap_or_default(),
+        binary_data: cm.binary_data
+            .iter()
+            .flatten()
+            .map(|(key, value)| (key.clone(), value.0.len()))
+            .collect(),
+        created_at: cm.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: cm.labels().clone(),
+    }
+}
+
+pub fn network_policy_info(np: &NetworkPolicy) -> NetworkPolicyInfo {
+    let spec = np.spec.as_ref();
+    
+    NetworkPolicyInfo {
+        name: np.name_any(),
+        namespace: np.namespace().unwrap_or_default(),
+        pod_selector: spec.map(|s| format_selector(&s.pod_selector)).unwrap_or_default(),
+        policy_types: crate::netpol::policy_types(np),
+        ingress_rules: spec
+            .and_then(|s| s.ingress.as_ref())
+            .map(|i| i.len())
+            .unwrap_or(0),
+        egress_rules: spec
+            .and_then(|s| s.egress.as_ref())
+            .map(|e| e.len())
+            .unwrap_or(0),
+        created_at: np.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: np.labels().clone(),
+    }
+}
+
+pub fn secret_info(secret: &Secret) -> SecretInfo {
+    let keys = secret.data
+        .iter()
+        .flatten()
+        .map(|(key, value)| SecretKeyInfo {
+            name: key.clone(),
+            size: value.0.len(),
+        })
+        .collect();
+
+    SecretInfo {
+        name: secret.name_any(),
+        namespace: secret.namespace().unwrap_or_default(),
+        secret_type: secret.type_.clone().unwrap_or_else(|| "Opaque".to_string()),
+        keys,
+        created_at: secret.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: secret.labels().clone(),
+    }
+}
+
+/// Events written through events.k8s.io leave the legacy count and timestamps
+/// empty, so fall back to the series and eventTime fields.
+pub fn event_info(event: &Event) -> EventInfo {
+    let event_time = event.event_time.as_ref().map(|t| t.0.to_rfc3339());
+    let first_timestamp = event.first_timestamp.as_ref()
+        .map(|t| t.0.to_rfc3339())
+        .or_else(|| event_time.clone())
+        .or_else(|| event.creation_timestamp().map(|ts| ts.0.to_rfc3339()));
+    let last_timestamp = event.last_timestamp.as_ref()
+        .map(|t| t.0.to_rfc3339())
+        .or_else(|| event.series.as_ref()
+            .and_then(|s| s.last_observed_time.as_ref())
+            .map(|t| t.0.to_rfc3339()))
+        .or_else(|| first_timestamp.clone());
+    let source = event.source.as_ref()
+        .and_then(|s| s.component.clone())
+        .or_else(|| event.reporting_component.clone())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_default();
+
+    EventInfo {
+        name: event.name_any(),
+        namespace: event.namespace().unwrap_or_default(),
+        reason: event.reason.clone().unwrap_or_default(),
+        message: event.message.clone().unwrap_or_default(),
+        event_type: event.type_.clone().unwrap_or_else(|| "Normal".to_string()),
+        count: event.count
+            .or_else(|| event.series.as_ref().and_then(|s| s.count))
+            .unwrap_or(1),
+        first_timestamp,
+        last_timestamp,
+        source,
+        involved_object: InvolvedObject {
+            kind: event.involved_object.kind.clone().unwrap_or_default(),
+            name: event.involved_object.name.clone().unwrap_or_default(),
+            namespace: event.involved_object.namespace.clone(),
+            field_path: event.involved_object.field_path.clone(),
+        },
+    }
+}
+
+pub async fn get_pod_details(namespace: String, pod_name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), &namespace);
+    
+    let pod = pods.get(&pod_name).await.map_err(reject)?;
+    let usage = metrics::pod_usage_one(client, &namespace, &pod_name).await;
+
+    let details = PodDetails {
+        name: pod.name_any(),
+        namespace: pod.namespace().unwrap_or_default(),
+        labels: pod.labels().clone(),
+        annotations: pod.annotations().clone(),
+        node_name: pod.spec.as_ref()
+            .and_then(|s| s.node_name.clone())
+            .unwrap_or_default(),
+        host_ip: pod.status.as_ref().and_then(|s| s.host_ip.clone()),
+        pod_ip: pod.status.as_ref().and_then(|s| s.pod_ip.clone()),
+        status: pod.status.as_ref()
+            .and_then(|s| s.phase.clone())
+            .unwrap_or_default(),
+        containers: pod.spec.as_ref()
+            .map(|s| s.containers.iter()
+                .map(|c| ContainerInfo {
+                    name: c.name.clone(),
+                    image: c.image.clone().unwrap_or_default(),
+                    ports: c.ports.as_ref()
+                        .map(|ports| ports.iter()
+                            .map(|p| format!("{}:{}", p.container_port, p.protocol.as_ref().unwrap_or(&"TCP".to_string())))
+                            .collect())
+                        .unwrap_or_default(),
+                    resources: format!(
+                        "CPU: {} / MEM: {}",
+                        c.resources.as_ref()
+                            .and_then(|r| r.requests.as_ref())
+                            .and_then(|req| req.get("cpu"))
+                            .map(|v| v.0.clone())
+                            .unwrap_or_default(),
+                        c.resources.as_ref()
+                            .and_then(|r| r.requests.as_ref())
+                            .and_then(|req| req.get("memory"))
+                            .map(|v| v.0.clone())
+                            .unwrap_or_default()
+                    ),
+                    requests: container_amounts(c, |r| r.requests.as_ref()),
+                    limits: container_amounts(c, |r| r.limits.as_ref()),
+                    usage: usage.as_ref().and_then(|u| u.containers.get(&c.name).cloned()),
+                })
+                .collect())
+            .unwrap_or_default(),
+        created_at: pod.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        usage: usage.map(|u| u.total),
+    };
+    
+    Ok(warp::reply::json(&details))
+}
+
+pub async fn get_pod_logs(namespace: String, pod_name: String, query: LogQuery, client: Client) -> Result<impl warp::Reply, Rejection> {
     let pods: Api<Pod> = Api::namespaced(client, &namespace);
     
-    match pods.logs(&pod_name, &Default::default()).await {
-        Ok(logs) => {
-            let log_response = PodLogs {
-                pod_name,
-                namespace,
-                logs,
-            };
-            Ok(warp::reply::json(&log_response))
+    let logs = pods.logs(&pod_name, &log_params(&query, false)).await.map_err(reject)?;
+
+    let log_response = PodLogs {
+        pod_name,
+        namespace,
+        logs,
+    };
+    Ok(warp::reply::json(&log_response))
+}
+
+pub async fn stream_pod_logs(
+    namespace: String,
+    pod_name: String,
+    query: LogQuery,
+    ws: warp::ws::Ws,
+    client: Client,
+) -> Result<impl warp::Reply, Rejection> {
+    let pods: Api<Pod> = Api::namespaced(client, &namespace);
+
+    // Open the log stream before upgrading so a missing pod or container is
+    // reported as a normal HTTP error instead of a silently closed socket.
+    let params = log_params(&query, query.follow.unwrap_or(true));
+    let stream = pods.log_stream(&pod_name, &params).await.map_err(reject)?;
+
+    Ok(ws.on_upgrade(move |socket| forward_log_lines(socket, stream)))
+}
+
+fn log_params(query: &LogQuery, follow: bool) -> LogParams {
+    LogParams {
+        container: query.container.clone(),
+        follow,
+        tail_lines: query.tail_lines,
+        since_seconds: query.since_seconds,
+        timestamps: query.timestamps,
+        previous: query.previous,
+        ..Default::default()
+    }
+}
+
+async fn forward_log_lines(socket: WebSocket, stream: impl AsyncBufRead + Unpin) {
+    let (mut tx, mut rx) = socket.split();
+    // Split on raw bytes: containers may log invalid UTF-8, which shouldn't end the stream
+    let lines = futures::stream::unfold(stream, |mut stream| async move {
+        let mut line = Vec::new();
+        match stream.read_until(b'\n', &mut line).await {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(line), stream)),
+            Err(e) => Some((Err(e), stream)),
         }
-        Err(e) => {
-            eprintln!("Error fetching pod logs: {}", e);
-            let error_response = PodLogs {
-                pod_name,
-                namespace,
-                logs: format!("Error fetching logs: {}", e),
-            };
-            Ok(warp::reply::json(&error_response))
+    });
+    futures::pin_mut!(lines);
+
+    loop {
+        tokio::select! {
+            line = lines.next() => match line {
+                Some(Ok(line)) => {
+                    let line = line.strip_suffix(b"\n").unwrap_or(&line);
+                    let line = line.strip_suffix(b"\r").unwrap_or(line);
+                    if tx.send(Message::text(String::from_utf8_lossy(line))).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Err(e)) => {
+                    eprintln!("Error reading log stream: {}", e);
+                    let _ = tx.send(Message::close_with(1011u16, "log stream error")).await;
+                    break;
+                }
+                None => {
+                    let _ = tx.send(Message::close()).await;
+                    break;
+                }
+            },
+            msg = rx.next() => match msg {
+                Some(Ok(msg)) if msg.is_close() => break,
+                Some(Ok(_)) => {}
+                _ => break,
+            },
         }
     }
-}
\ No newline at end of file
+}
@@ -1,17 +1,155 @@
 // src/k8s_client.rs
 use anyhow::Result;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::config::{KubeConfigOptions, Kubeconfig};
 use kube::{Client, Config};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-pub async fn create_client() -> Result<Client> {
-    let config = Config::infer().await?;
-    let client = Client::try_from(config)?;
-    Ok(client)
+use crate::auth::IdentityMapping;
+use crate::cache::ResourceCache;
+use crate::error::ApiError;
+use crate::models::{ContextInfo, Identity};
+
+pub async fn create_context_config(kubeconfig: Kubeconfig, context: &str) -> Result<Config> {
+    let options = KubeConfigOptions {
+        context: Some(context.to_string()),
+        ..Default::default()
+    };
+    let config = Config::from_custom_kubeconfig(kubeconfig, &options).await?;
+    Ok(config)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    context: Option<String>,
+    /// Kubernetes user and groups sent as `Impersonate-User` / `Impersonate-Group`.
+    impersonate: Option<(String, Vec<String>)>,
 }
 
+/// Holds one client and cache per kubeconfig context and impersonated user,
+/// created on first use. Requests without a context selector or identity use
+/// the inferred default client.
+#[derive(Clone)]
+pub struct ClusterRegistry {
+    kubeconfig: Option<Kubeconfig>,
+    default_config: Config,
+    default: ResourceCache,
+    impersonation: Option<Arc<IdentityMapping>>,
+    clusters: Arc<Mutex<HashMap<ClientKey, ResourceCache>>>,
+}
+
+impl ClusterRegistry {
+    /// Loads the kubeconfig and default client. When `impersonation` is set,
+    /// requests from signed-in users run as the mapped Kubernetes identity.
+    pub async fn load(impersonation: Option<IdentityMapping>) -> Result<Self> {
+        let kubeconfig = match Kubeconfig::read() {
+            Ok(kubeconfig) => Some(kubeconfig),
+            Err(e) => {
+                println!("No kubeconfig loaded ({}), context switching disabled", e);
+                None
+            }
+        };
+        let default_config = Config::infer().await?;
+        let default = ResourceCache::new(Client::try_from(default_config.clone())?);
+
+        Ok(ClusterRegistry {
+            kubeconfig,
+            default_config,
+            default,
+            impersonation: impersonation.map(Arc::new),
+            clusters: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn contexts(&self) -> Vec<ContextInfo> {
+        let Some(kubeconfig) = &self.kubeconfig else {
+            return Vec::new();
+        };
+        kubeconfig
+            .contexts
+            .iter()
+            .map(|named| {
+                let context = named.context.as_ref();
+                ContextInfo {
+                    name: named.name.clone(),
+                    cluster: context.map(|c| c.cluster.clone()).unwrap_or_default(),
+                    user: context.map(|c| c.user.clone()).unwrap_or_default(),
+                    namespace: context.and_then(|c| c.namespace.clone()),
+                    current: kubeconfig.current_context.as_deref() == Some(named.name.as_str()),
+                }
+            })
+            .collect()
+    }
+
+    /// Names the context a request runs against: the selected one, or for the default
+    /// client the kubeconfig's current context (`in-cluster` without a kubeconfig).
+    pub fn context_name(&self, context: Option<&str>) -> String {
+        context
+            .filter(|c| !c.is_empty())
+            .or_else(|| self.kubeconfig.as_ref()?.current_context.as_deref())
+            .unwrap_or("in-cluster")
+            .to_string()
+    }
+
+    /// Whether signed-in users' requests are sent as their own Kubernetes identity.
+    pub fn impersonates(&self) -> bool {
+        self.impersonation.is_some()
+    }
+
+    /// Returns the Kubernetes identity requests from `identity` run as, if impersonation is on.
+    pub fn kube_identity(&self, identity: Option<&Identity>) -> Option<Identity> {
+        let mapping = self.impersonation.as_ref()?;
+        identity.map(|identity| mapping.kube_identity(identity))
+    }
+
+    /// Returns the cache (and client) for the given context and caller, building it if needed.
+    /// Impersonated callers get their own cache so list results respect their RBAC.
+    pub async fn resolve(&self, context: Option<&str>, identity: Option<&Identity>) -> Result<ResourceCache, ApiError> {
+        let key = ClientKey {
+            context: context.filter(|c| !c.is_empty()).map(str::to_string),
+            impersonate: self
+                .kube_identity(identity)
+                .map(|kube| (kube.username, kube.groups)),
+        };
+        if key.context.is_none() && key.impersonate.is_none() {
+            return Ok(self.default.clone());
+        }
+        if let Some(cache) = self.clusters.lock().unwrap().get(&key) {
+            return Ok(cache.clone());
+        }
+
+        let mut config = match &key.context {
+            Some(context) => {
+                let kubeconfig = self
+                    .kubeconfig
+                    .as_ref()
+                    .filter(|kc| kc.contexts.iter().any(|c| &c.name == context))
+                    .ok_or_else(|| ApiError::not_found(format!("Unknown kubeconfig context: {}", context)))?;
+                create_context_config(kubeconfig.clone(), context)
+                    .await
+                    .map_err(|e| ApiError::bad_request(format!("Failed to load context {}: {}", context, e)))?
+            }
+            None => self.default_config.clone(),
+        };
+        if let Some((user, groups)) = &key.impersonate {
+            config.auth_info.impersonate = Some(user.clone());
+            config.auth_info.impersonate_groups = Some(groups.clone()).filter(|g| !g.is_empty());
+        }
+        let client = Client::try_from(config).map_err(|e| ApiError::internal(format!("Failed to create client: {}", e)))?;
+
+        let mut clusters = self.clusters.lock().unwrap();
+        let cache = clusters.entry(key).or_insert_with(|| ResourceCache::new(client));
+        Ok(cache.clone())
+    }
+}
+
+#[allow(dead_code)]
 pub fn format_age(timestamp: Option<String>) -> String {
     timestamp.unwrap_or_else(|| "Unknown".to_string())
 }
 
+#[allow(dead_code)]
 pub fn format_labels(labels: &std::collections::BTreeMap<String, String>) -> String {
     if labels.is_empty() {
         "<none>".to_string()
@@ -22,4 +160,23 @@ pub fn format_labels(labels: &std::collections::BTreeMap<String, String>) -> Str
             .collect::<Vec<_>>()
             .join(",")
     }
-}
\ No newline at end of file
+}
+
+/// Renders a label selector in the string form accepted by list calls.
+pub fn format_selector(selector: &LabelSelector) -> String {
+    let labels = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}={}", k, v));
+    let expressions = selector.match_expressions.iter().flatten().map(|expr| {
+        let values = expr.values.clone().unwrap_or_default().join(",");
+        match expr.operator.as_str() {
+            "In" => format!("{} in ({})", expr.key, values),
+            "NotIn" => format!("{} notin ({})", expr.key, values),
+            "DoesNotExist" => format!("!{}", expr.key),
+            _ => expr.key.clone(),
+        }
+    });
+    labels.chain(expressions).collect::<Vec<_>>().join(",")
+}
@@ -1,73 +1,559 @@
 // src/main.rs
 use anyhow::Result;
+use std::collections::HashMap;
 use std::convert::Infallible;
-use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::{Filter, Rejection, Reply};
 
+mod audit;
+mod auth;
+mod config;
+mod discovery;
+mod drain;
+mod error;
+mod exec;
 mod handlers;
 mod models;
+mod netpol;
+mod routing;
 mod k8s_client;
+mod metrics;
+mod cache;
+mod watch;
+mod yaml;
 
+use audit::{AuditLog, RequestAudit};
+use auth::{get_auth_info, login, logout, Authenticator, IdentityMapping, SESSION_COOKIE};
+use config::Settings;
+use discovery::{get_api_resources, get_dynamic_resources};
+use drain::drain_node;
+use error::handle_rejection;
+use exec::exec_pod;
 use handlers::*;
+use models::{
+    ApplyQuery, ConfigMapUpdate, DeletePodQuery, DrainRequest, EventQuery, ExecQuery, Identity, LogQuery, LoginRequest, ResourceQuery,
+    ReachabilityQuery, RevealSecretRequest, RollbackRequest, ScaleRequest, YamlQuery,
+};
+use cache::ResourceCache;
+use k8s_client::ClusterRegistry;
+use metrics::{get_metrics_history, MetricsHistory};
+use netpol::{check_reachability, get_network_policy};
+use routing::{get_http_routes, get_ingresses};
+use watch::watch_resources;
+use yaml::{apply_resource_yaml, get_resource_yaml};
+
+/// Body limit for small JSON requests such as login, scale and drain.
+const MAX_JSON_BODY: u64 = 16 * 1024;
+/// Body limit for whole objects (YAML apply, ConfigMap data). The API server itself
+/// caps requests at 3 MiB.
+const MAX_OBJECT_BODY: u64 = 3 * 1024 * 1024;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    // Initialize Kubernetes client
-    let client = kube::Client::try_default().await?;
+    let settings = Settings::from_env();
+    let authenticator = Authenticator::new(&settings.auth)?;
+    if !authenticator.is_enabled() {
+        println!("⚠️  No authentication configured, the API is open to anyone who can reach it");
+    }
+
+    let audit = AuditLog::open(settings.audit_log.as_deref())?;
+
+    // Signed-in users act with their own Kubernetes permissions unless impersonation is turned off
+    let impersonation = if authenticator.is_enabled() && settings.impersonation.enabled {
+        Some(match &settings.impersonation.mapping_file {
+            Some(path) => IdentityMapping::load(path)?,
+            None => IdentityMapping::default(),
+        })
+    } else {
+        None
+    };
+
+    // Initialize Kubernetes clients for the default and kubeconfig contexts
+    let registry = ClusterRegistry::load(impersonation).await?;
+
+    // Sample usage for the default context from startup; other contexts start on first request
+    let metrics_history = MetricsHistory::new(settings.metrics.clone(), registry.clone());
+    if metrics_history.is_enabled() {
+        metrics_history.start(None);
+    }
     
     // CORS configuration
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_headers(vec!["content-type"])
+        .allow_headers(vec!["authorization", "content-type", "x-kube-context"])
         .allow_methods(vec!["GET", "POST", "PUT", "DELETE"]);
 
-    // API routes
-    let api_routes = warp::path("api")
-        .and(
-            warp::path("namespaces")
-                .and(warp::get())
-                .and(with_client(client.clone()))
-                .and_then(get_namespaces)
-                .or(warp::path("pods")
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_pods))
-                .or(warp::path("services")
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_services))
-                .or(warp::path("deployments")
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_deployments))
-                .or(warp::path("configmaps")
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_configmaps))
-                .or(warp::path("networkpolicies")
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_network_policies))
-                .or(warp::path("pod")
-                    .and(warp::path::param::<String>())
-                    .and(warp::path::param::<String>())
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_pod_details))
-                .or(warp::path("pod")
-                    .and(warp::path::param::<String>())
-                    .and(warp::path::param::<String>())
-                    .and(warp::path("logs"))
-                    .and(warp::get())
-                    .and(with_client(client.clone()))
-                    .and_then(get_pod_logs))
-        );
+    // Login endpoints, reachable without a session
+    let auth_routes = warp::path("api").and(
+        warp::path("auth")
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_optional_identity(authenticator.clone()))
+            .and(with_authenticator(authenticator.clone()))
+            .and(with_registry(registry.clone()))
+            .and_then(get_auth_info)
+            .or(warp::path("login")
+                .and(warp::path::end())
+                .and(warp::post())
+                .and(warp::body::content_length_limit(MAX_JSON_BODY))
+                .and(warp::body::json::<LoginRequest>())
+                .and(with_authenticator(authenticator.clone()))
+                .and_then(login))
+            .or(warp::path("logout")
+                .and(warp::path::end())
+                .and(warp::post())
+                .and(warp::cookie::optional::<String>(SESSION_COOKIE))
+                .and(with_authenticator(authenticator.clone()))
+                .and_then(logout)),
+    );
+
+    // Cluster-wide views: contexts, namespaces, nodes and events
+    let cluster_routes = warp::path("contexts")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(require_auth(authenticator.clone()))
+        .and(with_registry(registry.clone()))
+        .and_then(get_contexts)
+        .or(warp::path("namespaces")
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_namespaces))
+        .or(warp::path("nodes")
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and(with_pod_count_cache(registry.clone(), settings.impersonation.shared_node_pod_counts))
+            .and_then(get_nodes))
+        .or(warp::path("node")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_node))
+        .or(warp::path("node")
+            .and(warp::path::param::<String>())
+            .and(warp::path("cordon"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(cordon_node))
+        .or(warp::path("node")
+            .and(warp::path::param::<String>())
+            .and(warp::path("uncordon"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(uncordon_node))
+        .or(warp::path("node")
+            .and(warp::path::param::<String>())
+            .and(warp::path("drain"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::content_length_limit(MAX_JSON_BODY))
+            .and(warp::body::json::<DrainRequest>())
+            .and(with_settings(settings.clone()))
+            .and(with_audited_client(registry.clone(), authenticator.clone(), audit.clone()))
+            .and_then(drain_node))
+        .or(warp::path("events")
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<EventQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|query, client| get_events(None, query, client)))
+        .or(warp::path("events")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<EventQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|namespace, query, client| get_events(Some(namespace), query, client)))
+        .or(warp::path!("metrics" / "history" / String / String)
+            .and(warp::get())
+            .and(with_context())
+            .and(with_metrics_history(metrics_history.clone()))
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, name, context, history, client| get_metrics_history(kind, None, name, context, history, client)))
+        .or(warp::path!("metrics" / "history" / String / String / String)
+            .and(warp::get())
+            .and(with_context())
+            .and(with_metrics_history(metrics_history.clone()))
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, namespace, name, context, history, client| {
+                get_metrics_history(kind, Some(namespace), name, context, history, client)
+            }))
+        .map(Reply::into_response)
+        .boxed();
+
+    // Namespaced resource lists and actions
+    let workload_routes = warp::path("pods")
+        .and(warp::path::param::<String>())
+        .and(warp::get())
+        .and(with_cache(registry.clone(), authenticator.clone()))
+        .and_then(get_pods)
+        .or(warp::path("services")
+            .and(warp::path::param::<String>())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_services))
+        .or(warp::path("service")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_service))
+        .or(warp::path("deployments")
+            .and(warp::path::param::<String>())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_deployments))
+        .or(warp::path("deployment")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_deployment))
+        .or(warp::path("deployment")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("scale"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::content_length_limit(MAX_JSON_BODY))
+            .and(warp::body::json::<ScaleRequest>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(scale_deployment))
+        .or(warp::path("deployment")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("restart"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(restart_deployment))
+        .or(warp::path("deployment")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("rollback"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::content_length_limit(MAX_JSON_BODY))
+            .and(warp::body::json::<RollbackRequest>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(rollback_deployment))
+        .or(warp::path("configmaps")
+            .and(warp::path::param::<String>())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_configmaps))
+        .or(warp::path("configmap")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(get_configmap))
+        .or(warp::path("configmap")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::put())
+            .and(warp::body::content_length_limit(MAX_OBJECT_BODY))
+            .and(warp::body::json::<ConfigMapUpdate>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(update_configmap))
+        .or(warp::path("secrets")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(get_secrets))
+        .or(warp::path("secret")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("reveal"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::body::content_length_limit(MAX_JSON_BODY))
+            .and(warp::body::json::<RevealSecretRequest>())
+            .and(with_settings(settings.clone()))
+            .and(with_audited_client(registry.clone(), authenticator.clone(), audit.clone()))
+            .and_then(reveal_secret))
+        .or(warp::path("networkpolicies")
+            .and(warp::path::param::<String>())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_network_policies))
+        .or(warp::path("networkpolicy")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(get_network_policy))
+        .or(warp::path("reachability")
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<ReachabilityQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(check_reachability))
+        .or(warp::path("ingresses")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_ingresses))
+        .or(warp::path("httproutes")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_http_routes))
+        .map(Reply::into_response)
+        .boxed();
+
+    // Workload controllers other than Deployments
+    let controller_routes = warp::path("replicasets")
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_cache(registry.clone(), authenticator.clone()))
+        .and_then(get_replica_sets)
+        .or(warp::path("replicaset")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_replica_set))
+        .or(warp::path("statefulsets")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_stateful_sets))
+        .or(warp::path("statefulset")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_stateful_set))
+        .or(warp::path("daemonsets")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_daemon_sets))
+        .or(warp::path("daemonset")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_daemon_set))
+        .or(warp::path("jobs")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_jobs))
+        .or(warp::path("job")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_job))
+        .or(warp::path("cronjobs")
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(get_cron_jobs))
+        .or(warp::path("cronjob")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(get_cron_job))
+        .or(warp::path("cronjob")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("trigger"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(trigger_cron_job))
+        .or(warp::path("cronjob")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("suspend"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(suspend_cron_job))
+        .or(warp::path("cronjob")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("resume"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(resume_cron_job))
+        .map(Reply::into_response)
+        .boxed();
+
+    // Single pod details, logs, exec and deletion
+    let pod_routes = warp::path("pod")
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_client(registry.clone(), authenticator.clone()))
+        .and_then(get_pod_details)
+        .or(warp::path("pod")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::delete())
+            .and(warp::query::<DeletePodQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(delete_pod))
+        .or(warp::path("pod")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("evict"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(evict_pod))
+        .or(warp::path("pod")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("logs"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<LogQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(get_pod_logs))
+        .or(warp::path("pod")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("logs"))
+            .and(warp::path("stream"))
+            .and(warp::path::end())
+            .and(warp::query::<LogQuery>())
+            .and(warp::ws())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(stream_pod_logs))
+        .or(warp::path("pod")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path("exec"))
+            .and(warp::path::end())
+            .and(warp::query::<ExecQuery>())
+            .and(warp::ws())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(exec_pod))
+        .map(Reply::into_response)
+        .boxed();
+
+    // Generic resources, YAML and watch streams
+    let resource_routes = warp::path("resources")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(with_cache(registry.clone(), authenticator.clone()))
+        .and_then(get_api_resources)
+        .or(warp::path("resources")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::tail())
+            .and(warp::get())
+            .and(warp::query::<ResourceQuery>())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(|group, version, kind, tail: warp::path::Tail, query, cache| {
+                let rest = tail.as_str().split('/').filter(|s| !s.is_empty()).map(str::to_string).collect();
+                get_dynamic_resources(group, version, kind, rest, query, cache)
+            }))
+        .or(warp::path("yaml")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<YamlQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, namespace, name, query, client| {
+                get_resource_yaml(kind, Some(namespace), name, query, client)
+            }))
+        .or(warp::path("yaml")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(warp::query::<YamlQuery>())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, name, query, client| get_resource_yaml(kind, None, name, query, client)))
+        .or(warp::path("yaml")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::query::<ApplyQuery>())
+            .and(warp::body::content_length_limit(MAX_OBJECT_BODY))
+            .and(warp::body::bytes())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, namespace, name, query, body, client| {
+                apply_resource_yaml(kind, Some(namespace), name, query, body, client)
+            }))
+        .or(warp::path("yaml")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::post())
+            .and(warp::query::<ApplyQuery>())
+            .and(warp::body::content_length_limit(MAX_OBJECT_BODY))
+            .and(warp::body::bytes())
+            .and(with_client(registry.clone(), authenticator.clone()))
+            .and_then(|kind, name, query, body, client| {
+                apply_resource_yaml(kind, None, name, query, body, client)
+            }))
+        .or(warp::path("watch")
+            .and(warp::path("namespaces"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(|cache| watch_resources("namespaces".to_string(), None, cache)))
+        .or(warp::path("watch")
+            .and(warp::path::param::<String>())
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::get())
+            .and(with_cache(registry.clone(), authenticator.clone()))
+            .and_then(|kind, namespace, cache| watch_resources(kind, Some(namespace), cache)))
+        .map(Reply::into_response)
+        .boxed();
+
+    // API routes, grouped and boxed so the combined filter stays shallow. Each route
+    // authenticates once, through `with_session` or `require_auth`.
+    let api_routes = warp::path("api").and(
+        cluster_routes
+            .or(workload_routes)
+            .unify()
+            .or(controller_routes)
+            .unify()
+            .or(pod_routes)
+            .unify()
+            .or(resource_routes)
+            .unify(),
+    );
 
     // Static file serving
     let static_files = warp::fs::dir("static");
@@ -78,8 +564,10 @@ async fn main() -> Result<()> {
     });
 
     let routes = root
+        .or(auth_routes)
         .or(api_routes)
         .or(static_files)
+        .recover(handle_rejection)
         .with(warp::log("k8s_dashboard"))
         .with(cors);
 
@@ -92,6 +580,114 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-fn with_client(client: kube::Client) -> impl Filter<Extract = (kube::Client,), Error = Infallible> + Clone {
-    warp::any().map(move || client.clone())
-}
\ No newline at end of file
+fn with_authenticator(auth: Authenticator) -> impl Filter<Extract = (Authenticator,), Error = Infallible> + Clone {
+    warp::any().map(move || auth.clone())
+}
+
+/// Authenticates the request from the session cookie or `Authorization` header,
+/// rejecting with 401 when authentication is enabled and the caller is unknown.
+fn with_identity(auth: Authenticator) -> impl Filter<Extract = (Option<Identity>,), Error = Rejection> + Clone {
+    warp::cookie::optional::<String>(SESSION_COOKIE)
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |cookie: Option<String>, authorization: Option<String>| {
+            let auth = auth.clone();
+            async move {
+                auth.authenticate(cookie, authorization)
+                    .await
+                    .map_err(warp::reject::custom)
+            }
+        })
+}
+
+fn with_optional_identity(auth: Authenticator) -> impl Filter<Extract = (Option<Identity>,), Error = Infallible> + Clone {
+    with_identity(auth).or(warp::any().map(|| None)).unify()
+}
+
+fn require_auth(auth: Authenticator) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    with_identity(auth).map(|_| ()).untuple_one()
+}
+
+fn with_settings(settings: Settings) -> impl Filter<Extract = (Settings,), Error = Infallible> + Clone {
+    warp::any().map(move || settings.clone())
+}
+
+fn with_registry(registry: ClusterRegistry) -> impl Filter<Extract = (ClusterRegistry,), Error = Infallible> + Clone {
+    warp::any().map(move || registry.clone())
+}
+
+fn with_metrics_history(history: MetricsHistory) -> impl Filter<Extract = (MetricsHistory,), Error = Infallible> + Clone {
+    warp::any().map(move || history.clone())
+}
+
+/// Reads the kubeconfig context from the `X-Kube-Context` header or the `context`
+/// query parameter (for EventSource and WebSocket, which can't set headers).
+fn with_context() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("x-kube-context")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|header: Option<String>, query: HashMap<String, String>| header.or_else(|| query.get("context").cloned()))
+}
+
+/// Authenticates the caller and resolves the client for the requested context,
+/// impersonating the signed-in user. API routes take their identity from here
+/// rather than authenticating again. Boxed to keep the route chain's nested
+/// filter types (and debug-build stack usage) in check.
+fn with_session(registry: ClusterRegistry, auth: Authenticator) -> BoxedFilter<(Option<String>, Option<Identity>, ResourceCache)> {
+    with_context()
+        .and(with_identity(auth))
+        .and_then(move |context: Option<String>, identity: Option<Identity>| {
+            let registry = registry.clone();
+            async move {
+                let cache = registry
+                    .resolve(context.as_deref(), identity.as_ref())
+                    .await
+                    .map_err(warp::reject::custom)?;
+                Ok::<_, Rejection>((context, identity, cache))
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// Like `with_client`, plus the audit log bound to the caller and the context name.
+fn with_audited_client(
+    registry: ClusterRegistry,
+    auth: Authenticator,
+    audit: AuditLog,
+) -> impl Filter<Extract = (RequestAudit, kube::Client), Error = Rejection> + Clone {
+    with_session(registry.clone(), auth)
+        .map(move |context: Option<String>, identity, cache: ResourceCache| {
+            let audit = audit.for_request(identity, registry.context_name(context.as_deref()));
+            (audit, cache.client())
+        })
+        .untuple_one()
+}
+
+/// The requested context's cache under the server's own credentials, used to count
+/// pods per node for everyone when that's allowed: without impersonation, or when
+/// `shared` is set. Only use it after the caller is authenticated.
+fn with_pod_count_cache(
+    registry: ClusterRegistry,
+    shared: bool,
+) -> impl Filter<Extract = (Option<ResourceCache>,), Error = Rejection> + Clone {
+    with_context().and_then(move |context: Option<String>| {
+        let registry = registry.clone();
+        async move {
+            if !shared && registry.impersonates() {
+                return Ok(None);
+            }
+            registry
+                .resolve(context.as_deref(), None)
+                .await
+                .map(Some)
+                .map_err(warp::reject::custom)
+        }
+    })
+}
+
+fn with_client(registry: ClusterRegistry, auth: Authenticator) -> impl Filter<Extract = (kube::Client,), Error = Rejection> + Clone {
+    with_session(registry, auth).map(|_, _, cache: ResourceCache| cache.client())
+}
+
+fn with_cache(registry: ClusterRegistry, auth: Authenticator) -> impl Filter<Extract = (ResourceCache,), Error = Rejection> + Clone {
+    with_session(registry, auth).map(|_, _, cache| cache)
+}
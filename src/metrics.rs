@@ -0,0 +1,472 @@
+// src/metrics.rs
+use k8s_openapi::api::core::v1::{Node, Pod};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::chrono::Utc;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind, ListParams};
+use kube::{Api, Client, ResourceExt};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use warp::Rejection;
+
+use crate::config::MetricsSettings;
+use crate::error::{reject, ApiError};
+use crate::k8s_client::ClusterRegistry;
+use crate::models::{MetricsHistoryResponse, MetricsSample, ResourceAmounts};
+
+/// `(kind, namespace, name)` of a sampled object; kind is `pods` or `nodes`.
+type SeriesKey = (&'static str, Option<String>, String);
+
+type Store = Arc<Mutex<HashMap<SeriesKey, Series>>>;
+
+/// PodMetrics keyed by `(namespace, name)`.
+pub type PodUsageMap = HashMap<(String, String), PodUsage>;
+/// NodeMetrics keyed by node name.
+pub type NodeUsageMap = HashMap<String, ResourceAmounts>;
+
+/// How long a metrics.k8s.io list is reused; metrics-server scrapes every 15s by default.
+const USAGE_TTL: Duration = Duration::from_secs(15);
+
+/// Set once the metrics API has been reported unavailable, so it's only logged once.
+static METRICS_UNAVAILABLE_LOGGED: AtomicBool = AtomicBool::new(false);
+
+struct Series {
+    samples: VecDeque<MetricsSample>,
+    last_seen: Instant,
+}
+
+/// Usage history sampled in the background, one sampler per kubeconfig context.
+/// Samplers read with the server's own credentials; the history endpoint checks
+/// that the caller may get the object before returning its samples.
+#[derive(Clone)]
+pub struct MetricsHistory {
+    settings: MetricsSettings,
+    registry: ClusterRegistry,
+    contexts: Arc<Mutex<HashMap<Option<String>, Store>>>,
+}
+
+impl MetricsHistory {
+    pub fn new(settings: MetricsSettings, registry: ClusterRegistry) -> Self {
+        MetricsHistory {
+            settings,
+            registry,
+            contexts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.settings.retention.is_zero()
+    }
+
+    fn capacity(&self) -> usize {
+        let ratio = self.settings.retention.as_secs_f64() / self.settings.sample_interval.as_secs_f64();
+        (ratio.ceil() as usize).max(1)
+    }
+
+    /// Returns the store for a context, starting its sampler on first use.
+    fn store(&self, context: Option<String>) -> Store {
+        let mut contexts = self.contexts.lock().unwrap();
+        if let Some(store) = contexts.get(&context) {
+            return store.clone();
+        }
+        let store = Arc::new(Mutex::new(HashMap::new()));
+        contexts.insert(context.clone(), store.clone());
+        if self.is_enabled() {
+            tokio::spawn(self.clone().sample(context, store.clone()));
+        }
+        store
+    }
+
+    pub fn start(&self, context: Option<String>) {
+        self.store(context);
+    }
+
+    async fn sample(self, context: Option<String>, store: Store) {
+        let client = match self.registry.resolve(context.as_deref(), None).await {
+            Ok(cache) => cache.client(),
+            Err(e) => {
+                eprintln!("Metrics sampler for {:?} not started: {}", context, e.message);
+                return;
+            }
+        };
+        let capacity = self.capacity();
+        let mut interval = tokio::time::interval(self.settings.sample_interval);
+
+        loop {
+            interval.tick().await;
+            let pods = pod_usage(client.clone(), None).await;
+            let nodes = node_usage(client.clone()).await;
+            let timestamp = Utc::now().to_rfc3339();
+            let now = Instant::now();
+
+            let usage = pods
+                .into_iter()
+                .flatten()
+                .map(|((ns, name), usage)| (("pods", Some(ns), name), usage.total))
+                .chain(nodes.into_iter().flatten().map(|(name, usage)| (("nodes", None, name), usage)));
+
+            let mut store = store.lock().unwrap();
+            for (key, usage) in usage {
+                let series = store.entry(key).or_insert_with(|| Series {
+                    samples: VecDeque::with_capacity(capacity),
+                    last_seen: now,
+                });
+                if series.samples.len() == capacity {
+                    series.samples.pop_front();
+                }
+                series.samples.push_back(MetricsSample {
+                    timestamp: timestamp.clone(),
+                    cpu_millis: usage.cpu_millis,
+                    memory_bytes: usage.memory_bytes,
+                });
+                series.last_seen = now;
+            }
+            // Forget objects that have been gone for longer than the retention
+            store.retain(|_, series| now.duration_since(series.last_seen) <= self.settings.retention);
+        }
+    }
+
+    fn samples(&self, context: Option<String>, key: &SeriesKey) -> Vec<MetricsSample> {
+        let store = self.store(context);
+        let store = store.lock().unwrap();
+        store
+            .get(key)
+            .map(|series| series.samples.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Returns sampled usage for a pod or node. The object is fetched with the caller's
+/// client first, so history is only shown to users who can see the object.
+pub async fn get_metrics_history(
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+    context: Option<String>,
+    history: MetricsHistory,
+    client: Client,
+) -> Result<impl warp::Reply, Rejection> {
+    let kind: &'static str = match (kind.as_str(), &namespace) {
+        ("pods", Some(ns)) => {
+            Api::<Pod>::namespaced(client, ns).get_metadata(&name).await.map_err(reject)?;
+            "pods"
+        }
+        ("nodes", None) => {
+            Api::<Node>::all(client).get_metadata(&name).await.map_err(reject)?;
+            "nodes"
+        }
+        _ => return Err(warp::reject::not_found()),
+    };
+    if !history.is_enabled() {
+        return Err(warp::reject::custom(ApiError::not_found("Metrics history is disabled")));
+    }
+
+    let context = context.filter(|c| !c.is_empty());
+    let samples = history.samples(context, &(kind, namespace.clone(), name.clone()));
+    Ok(warp::reply::json(&MetricsHistoryResponse {
+        kind: kind.to_string(),
+        namespace,
+        name,
+        interval_seconds: history.settings.sample_interval.as_secs(),
+        retention_seconds: history.settings.retention.as_secs(),
+        samples,
+    }))
+}
+
+struct Fetched<T> {
+    at: Instant,
+    value: Option<Arc<T>>,
+}
+
+impl<T> Fetched<T> {
+    fn fresh(&self) -> bool {
+        self.at.elapsed() < USAGE_TTL
+    }
+}
+
+/// Usage lists recently read with one client, so list and detail views, including
+/// those served from the reflector cache, don't query metrics-server on every request.
+#[derive(Clone, Default)]
+pub struct UsageCache {
+    pods: Arc<Mutex<HashMap<Option<String>, Fetched<PodUsageMap>>>>,
+    nodes: Arc<Mutex<Option<Fetched<NodeUsageMap>>>>,
+}
+
+impl UsageCache {
+    /// Like `pod_usage`, reusing a list younger than `USAGE_TTL`.
+    pub async fn pods(&self, client: Client, namespace: Option<&str>) -> Option<Arc<PodUsageMap>> {
+        let key = namespace.map(str::to_string);
+        if let Some(fetched) = self.pods.lock().unwrap().get(&key).filter(|f| f.fresh()) {
+            return fetched.value.clone();
+        }
+        let value = pod_usage(client, namespace).await.map(Arc::new);
+        let mut pods = self.pods.lock().unwrap();
+        pods.retain(|_, fetched| fetched.fresh());
+        pods.insert(key, Fetched { at: Instant::now(), value: value.clone() });
+        value
+    }
+
+    /// Like `node_usage`, reusing a list younger than `USAGE_TTL`.
+    pub async fn nodes(&self, client: Client) -> Option<Arc<NodeUsageMap>> {
+        if let Some(fetched) = self.nodes.lock().unwrap().as_ref().filter(|f| f.fresh()) {
+            return fetched.value.clone();
+        }
+        let value = node_usage(client).await.map(Arc::new);
+        *self.nodes.lock().unwrap() = Some(Fetched { at: Instant::now(), value: value.clone() });
+        value
+    }
+}
+
+/// Usage of one pod as reported by metrics-server, in total and per container.
+#[derive(Debug, Clone, Default)]
+pub struct PodUsage {
+    pub total: ResourceAmounts,
+    pub containers: HashMap<String, ResourceAmounts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodMetricsData {
+    #[serde(default)]
+    containers: Vec<ContainerMetrics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContainerMetrics {
+    name: String,
+    #[serde(default)]
+    usage: BTreeMap<String, Quantity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeMetricsData {
+    #[serde(default)]
+    usage: BTreeMap<String, Quantity>,
+}
+
+fn metrics_resource(kind: &str, plural: &str) -> ApiResource {
+    ApiResource::from_gvk_with_plural(&GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", kind), plural)
+}
+
+/// Lists PodMetrics keyed by `(namespace, name)`. Returns `None` when the metrics
+/// API isn't served (no metrics-server) or can't be read, so callers just omit usage.
+pub async fn pod_usage(client: Client, namespace: Option<&str>) -> Option<PodUsageMap> {
+    let resource = metrics_resource("PodMetrics", "pods");
+    let api: Api<DynamicObject> = match namespace {
+        Some(ns) => Api::namespaced_with(client, ns, &resource),
+        None => Api::all_with(client, &resource),
+    };
+    let list = list_metrics(&api, &ListParams::default()).await?;
+
+    Some(
+        list.into_iter()
+            .map(|obj| {
+                let key = (obj.namespace().unwrap_or_default(), obj.name_any());
+                (key, pod_usage_of(obj))
+            })
+            .collect(),
+    )
+}
+
+/// Usage of a single pod, or `None` if metrics are unavailable or not yet collected.
+pub async fn pod_usage_one(client: Client, namespace: &str, name: &str) -> Option<PodUsage> {
+    let api: Api<DynamicObject> = Api::namespaced_with(client, namespace, &metrics_resource("PodMetrics", "pods"));
+    let list = list_metrics(&api, &ListParams::default().fields(&format!("metadata.name={}", name))).await?;
+    list.into_iter().next().map(pod_usage_of)
+}
+
+/// Lists NodeMetrics keyed by node name; `None` when the metrics API is unavailable.
+pub async fn node_usage(client: Client) -> Option<NodeUsageMap> {
+    let api: Api<DynamicObject> = Api::all_with(client, &metrics_resource("NodeMetrics", "nodes"));
+    let list = list_metrics(&api, &ListParams::default()).await?;
+
+    Some(
+        list.into_iter()
+            .map(|obj| {
+                let name = obj.name_any();
+                let usage = serde_json::from_value::<NodeMetricsData>(obj.data)
+                    .map(|data| amounts(&data.usage))
+                    .unwrap_or_default();
+                (name, usage)
+            })
+            .collect(),
+    )
+}
+
+async fn list_metrics(api: &Api<DynamicObject>, params: &ListParams) -> Option<Vec<DynamicObject>> {
+    match api.list(params).await {
+        Ok(list) => Some(list.items),
+        // 404 when metrics.k8s.io isn't registered, 503 while its APIService is unavailable
+        Err(kube::Error::Api(resp)) if resp.code == 404 || resp.code == 503 => None,
+        Err(e) => {
+            if !METRICS_UNAVAILABLE_LOGGED.swap(true, Ordering::Relaxed) {
+                eprintln!("Failed to read metrics, usage will be omitted: {}", e);
+            }
+            None
+        }
+    }
+}
+
+fn pod_usage_of(obj: DynamicObject) -> PodUsage {
+    let containers: HashMap<String, ResourceAmounts> = serde_json::from_value::<PodMetricsData>(obj.data)
+        .map(|data| data.containers)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| (c.name, amounts(&c.usage)))
+        .collect();
+    let total = sum(containers.values());
+    PodUsage { total, containers }
+}
+
+/// Reads the cpu and memory entries of a requests, limits or usage map.
+pub fn amounts(quantities: &BTreeMap<String, Quantity>) -> ResourceAmounts {
+    ResourceAmounts {
+        cpu_millis: quantities.get("cpu").and_then(|q| parse_quantity(&q.0)).map(|cores| cores * 1000.0),
+        memory_bytes: quantities.get("memory").and_then(|q| parse_quantity(&q.0)),
+    }
+}
+
+/// Adds up amounts; a resource stays `None` only if no entry sets it.
+pub fn sum<'a>(items: impl IntoIterator<Item = &'a ResourceAmounts>) -> ResourceAmounts {
+    items.into_iter().fold(ResourceAmounts::default(), |acc, item| ResourceAmounts {
+        cpu_millis: add(acc.cpu_millis, item.cpu_millis),
+        memory_bytes: add(acc.memory_bytes, item.memory_bytes),
+    })
+}
+
+fn add(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Parses a Kubernetes quantity such as `250m`, `1.5`, `128974848`, `129e6` or `512Mi`
+/// into its base unit (cores or bytes).
+pub fn parse_quantity(value: &str) -> Option<f64> {
+    let value = value.trim();
+    // `e`/`E` is an exponent only when digits follow it; otherwise `E` is the exa suffix
+    let split = value
+        .char_indices()
+        .find(|&(i, c)| c.is_ascii_alphabetic() && !(matches!(c, 'e' | 'E') && is_exponent(&value[i + 1..])))
+        .map_or(value.len(), |(i, _)| i);
+    let (number, suffix) = value.split_at(split);
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024.0_f64.powi(2),
+        "Gi" => 1024.0_f64.powi(3),
+        "Ti" => 1024.0_f64.powi(4),
+        "Pi" => 1024.0_f64.powi(5),
+        "Ei" => 1024.0_f64.powi(6),
+        _ => return None,
+    };
+    number.parse::<f64>().ok().map(|n| n * multiplier)
+}
+
+fn is_exponent(rest: &str) -> bool {
+    let digits = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantities(cpu: &str, memory: &str) -> BTreeMap<String, Quantity> {
+        BTreeMap::from([
+            ("cpu".to_string(), Quantity(cpu.to_string())),
+            ("memory".to_string(), Quantity(memory.to_string())),
+        ])
+    }
+
+    #[test]
+    fn parses_plain_and_decimal_suffixes() {
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity(" 1.5 "), Some(1.5));
+        assert_eq!(parse_quantity("250m"), Some(0.25));
+        assert_eq!(parse_quantity("1u"), Some(1e-6));
+        assert_eq!(parse_quantity("1n"), Some(1e-9));
+        assert_eq!(parse_quantity("3k"), Some(3e3));
+        assert_eq!(parse_quantity("129M"), Some(129e6));
+        assert_eq!(parse_quantity("2G"), Some(2e9));
+        assert_eq!(parse_quantity("1T"), Some(1e12));
+        assert_eq!(parse_quantity("1P"), Some(1e15));
+        assert_eq!(parse_quantity("1E"), Some(1e18));
+    }
+
+    #[test]
+    fn parses_binary_suffixes() {
+        assert_eq!(parse_quantity("1Ki"), Some(1024.0));
+        assert_eq!(parse_quantity("512Mi"), Some(512.0 * 1024.0 * 1024.0));
+        assert_eq!(parse_quantity("1Gi"), Some(1024.0_f64.powi(3)));
+        assert_eq!(parse_quantity("1Ti"), Some(1024.0_f64.powi(4)));
+        assert_eq!(parse_quantity("1Pi"), Some(1024.0_f64.powi(5)));
+        assert_eq!(parse_quantity("1Ei"), Some(1024.0_f64.powi(6)));
+    }
+
+    #[test]
+    fn parses_exponents() {
+        assert_eq!(parse_quantity("129e6"), Some(129e6));
+        assert_eq!(parse_quantity("1E3"), Some(1e3));
+        assert_eq!(parse_quantity("1.5e+3"), Some(1.5e3));
+        assert_eq!(parse_quantity("5e-3"), Some(5e-3));
+    }
+
+    #[test]
+    fn rejects_invalid_quantities() {
+        assert_eq!(parse_quantity(""), None);
+        assert_eq!(parse_quantity("abc"), None);
+        assert_eq!(parse_quantity("1Xi"), None);
+        assert_eq!(parse_quantity("1e"), None);
+        assert_eq!(parse_quantity("1ki"), None);
+    }
+
+    #[test]
+    fn amounts_converts_cpu_to_millis() {
+        let amounts = amounts(&quantities("250m", "64Mi"));
+        assert_eq!(amounts.cpu_millis, Some(250.0));
+        assert_eq!(amounts.memory_bytes, Some(64.0 * 1024.0 * 1024.0));
+
+        let partial = super::amounts(&BTreeMap::from([("cpu".to_string(), Quantity("2".to_string()))]));
+        assert_eq!(partial.cpu_millis, Some(2000.0));
+        assert_eq!(partial.memory_bytes, None);
+
+        let invalid = super::amounts(&quantities("lots", "1Gi"));
+        assert_eq!(invalid.cpu_millis, None);
+        assert_eq!(invalid.memory_bytes, Some(1024.0_f64.powi(3)));
+    }
+
+    #[test]
+    fn sum_keeps_unset_resources_unset() {
+        let items = [
+            ResourceAmounts { cpu_millis: Some(100.0), memory_bytes: None },
+            ResourceAmounts { cpu_millis: Some(150.0), memory_bytes: None },
+        ];
+        let total = sum(&items);
+        assert_eq!(total.cpu_millis, Some(250.0));
+        assert_eq!(total.memory_bytes, None);
+
+        let mixed = [
+            ResourceAmounts { cpu_millis: None, memory_bytes: Some(1024.0) },
+            ResourceAmounts { cpu_millis: Some(50.0), memory_bytes: Some(2048.0) },
+        ];
+        let total = sum(&mixed);
+        assert_eq!(total.cpu_millis, Some(50.0));
+        assert_eq!(total.memory_bytes, Some(3072.0));
+
+        let empty = sum(&[]);
+        assert_eq!(empty.cpu_millis, None);
+        assert_eq!(empty.memory_bytes, None);
+    }
+}
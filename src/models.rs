@@ -2,6 +2,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContextInfo {
+    pub name: String,
+    pub cluster: String,
+    pub user: String,
+    pub namespace: Option<String>,
+    pub current: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NamespaceInfo {
     pub name: String,
@@ -18,8 +27,18 @@ pub struct PodInfo {
     pub ready: String,
     pub restarts: i32,
     pub created_at: Option<String>,
+    /// Node the pod is scheduled to (`spec.nodeName`).
     pub node_name: Option<String>,
+    /// IP of that node as reported by the kubelet (`status.hostIP`).
+    pub host_ip: Option<String>,
     pub labels: BTreeMap<String, String>,
+    /// Sum of the containers' requests and limits.
+    pub requests: ResourceAmounts,
+    pub limits: ResourceAmounts,
+    /// Live usage from metrics.k8s.io, set by the pod list and by the pod tables of the
+    /// service, workload, job and node detail views. `None` without metrics-server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ResourceAmounts>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,6 +53,51 @@ pub struct ServiceInfo {
     pub labels: BTreeMap<String, String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct ServiceDetails {
+    #[serde(flatten)]
+    pub service: ServiceInfo,
+    /// Empty for services without a selector.
+    pub selector: String,
+    pub external_name: Option<String>,
+    pub session_affinity: String,
+    /// Structured form of `ports`.
+    pub service_ports: Vec<ServicePortInfo>,
+    /// Distinct addresses that are ready on at least one port.
+    pub ready_endpoints: usize,
+    pub endpoints: Vec<EndpointPortInfo>,
+    /// Pods matching the selector, whether or not they are ready.
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServicePortInfo {
+    pub name: Option<String>,
+    pub port: i32,
+    pub target_port: Option<String>,
+    pub node_port: Option<i32>,
+    pub protocol: String,
+}
+
+/// Addresses published for one port across all of a service's EndpointSlices.
+#[derive(Debug, Serialize)]
+pub struct EndpointPortInfo {
+    pub name: Option<String>,
+    pub port: Option<i32>,
+    pub protocol: String,
+    pub ready: Vec<EndpointAddressInfo>,
+    pub not_ready: Vec<EndpointAddressInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointAddressInfo {
+    pub address: String,
+    pub pod: Option<String>,
+    pub node: Option<String>,
+    pub serving: bool,
+    pub terminating: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeploymentInfo {
     pub name: String,
@@ -46,6 +110,208 @@ pub struct DeploymentInfo {
     pub labels: BTreeMap<String, String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DeploymentDetails {
+    #[serde(flatten)]
+    pub deployment: DeploymentInfo,
+    pub annotations: BTreeMap<String, String>,
+    /// `RollingUpdate` or `Recreate`.
+    pub strategy: String,
+    pub max_surge: Option<String>,
+    pub max_unavailable: Option<String>,
+    pub selector: String,
+    pub revision: Option<i64>,
+    pub paused: bool,
+    pub conditions: Vec<ConditionInfo>,
+    pub containers: Vec<ContainerImage>,
+    /// Owned ReplicaSets, newest revision first.
+    pub replica_sets: Vec<ReplicaSetInfo>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContainerImage {
+    pub name: String,
+    pub image: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplicaSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub revision: Option<i64>,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    pub available_replicas: i32,
+    pub images: Vec<String>,
+    /// Pods owned by the ReplicaSet; only filled in by the deployment detail endpoint.
+    pub pod_count: Option<usize>,
+    /// Controlling object as `Kind/name`, e.g. `Deployment/web`.
+    pub owner: Option<String>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplicaSetDetails {
+    #[serde(flatten)]
+    pub replica_set: ReplicaSetInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub selector: String,
+    pub conditions: Vec<ConditionInfo>,
+    pub containers: Vec<ContainerImage>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatefulSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub replicas: i32,
+    pub ready_replicas: i32,
+    pub current_replicas: i32,
+    pub updated_replicas: i32,
+    pub service_name: String,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatefulSetDetails {
+    #[serde(flatten)]
+    pub stateful_set: StatefulSetInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub selector: String,
+    /// `RollingUpdate` or `OnDelete`.
+    pub update_strategy: String,
+    /// Ordinals below the partition keep the current revision during a rolling update.
+    pub partition: Option<i32>,
+    pub pod_management_policy: String,
+    pub current_revision: Option<String>,
+    pub update_revision: Option<String>,
+    pub volume_claim_templates: Vec<String>,
+    pub conditions: Vec<ConditionInfo>,
+    pub containers: Vec<ContainerImage>,
+    /// One entry per desired ordinal, plus any pods left beyond the replica count.
+    pub ordinals: Vec<OrdinalStatus>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrdinalStatus {
+    pub ordinal: i32,
+    pub pod_name: String,
+    /// Pod phase, or `None` if the pod doesn't exist.
+    pub phase: Option<String>,
+    pub ready: bool,
+    /// Value of the pod's `controller-revision-hash` label.
+    pub revision: Option<String>,
+    pub updated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonSetInfo {
+    pub name: String,
+    pub namespace: String,
+    pub desired: i32,
+    pub current: i32,
+    pub ready: i32,
+    pub up_to_date: i32,
+    pub available: i32,
+    pub misscheduled: i32,
+    pub node_selector: BTreeMap<String, String>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DaemonSetDetails {
+    #[serde(flatten)]
+    pub daemon_set: DaemonSetInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub selector: String,
+    /// `RollingUpdate` or `OnDelete`.
+    pub update_strategy: String,
+    pub max_unavailable: Option<String>,
+    pub tolerations: Vec<String>,
+    pub conditions: Vec<ConditionInfo>,
+    pub containers: Vec<ContainerImage>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub name: String,
+    pub namespace: String,
+    /// `Running`, `Complete`, `Failed` or `Suspended`.
+    pub status: String,
+    /// Required successful pods; `None` when any single success completes the job.
+    pub completions: Option<i32>,
+    pub parallelism: i32,
+    pub active: i32,
+    pub succeeded: i32,
+    pub failed: i32,
+    pub start_time: Option<String>,
+    pub completion_time: Option<String>,
+    /// Name of the CronJob that created the job, if any.
+    pub cron_job: Option<String>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobDetails {
+    #[serde(flatten)]
+    pub job: JobInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub backoff_limit: Option<i32>,
+    pub active_deadline_seconds: Option<i64>,
+    pub ttl_seconds_after_finished: Option<i32>,
+    pub conditions: Vec<ConditionInfo>,
+    pub containers: Vec<ContainerImage>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CronJobInfo {
+    pub name: String,
+    pub namespace: String,
+    pub schedule: String,
+    pub time_zone: Option<String>,
+    pub suspend: bool,
+    pub active: usize,
+    pub last_schedule_time: Option<String>,
+    pub last_successful_time: Option<String>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CronJobDetails {
+    #[serde(flatten)]
+    pub cron_job: CronJobInfo,
+    pub annotations: BTreeMap<String, String>,
+    /// `Allow`, `Forbid` or `Replace`.
+    pub concurrency_policy: String,
+    pub starting_deadline_seconds: Option<i64>,
+    pub successful_jobs_history_limit: Option<i32>,
+    pub failed_jobs_history_limit: Option<i32>,
+    /// Names of the currently running jobs.
+    pub active_jobs: Vec<String>,
+    pub containers: Vec<ContainerImage>,
+    /// Jobs created by the CronJob, newest first.
+    pub jobs: Vec<CronJobRun>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CronJobRun {
+    #[serde(flatten)]
+    pub job: JobInfo,
+    /// `true` for jobs started from the dashboard rather than by the schedule.
+    pub manual: bool,
+    pub pods: Vec<PodInfo>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigMapInfo {
     pub name: String,
@@ -59,19 +325,165 @@ pub struct ConfigMapInfo {
 pub struct NetworkPolicyInfo {
     pub name: String,
     pub namespace: String,
-    pub pod_selector: BTreeMap<String, String>,
+    /// Selector string including `matchExpressions`; empty selects every pod.
+    pub pod_selector: String,
+    /// Effective types, `Ingress` and/or `Egress`.
+    pub policy_types: Vec<String>,
     pub ingress_rules: usize,
     pub egress_rules: usize,
     pub created_at: Option<String>,
     pub labels: BTreeMap<String, String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct NetworkPolicyDetails {
+    #[serde(flatten)]
+    pub policy: NetworkPolicyInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub ingress: Vec<NetworkPolicyRuleInfo>,
+    pub egress: Vec<NetworkPolicyRuleInfo>,
+}
+
+/// One rule; empty `peers` or `ports` means any.
+#[derive(Debug, Serialize)]
+pub struct NetworkPolicyRuleInfo {
+    pub peers: Vec<NetworkPolicyPeerInfo>,
+    /// E.g. `TCP/80`, `TCP/8000-8080` or `UDP/dns`.
+    pub ports: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetworkPolicyPeerInfo {
+    pub pod_selector: Option<String>,
+    pub namespace_selector: Option<String>,
+    pub ip_block: Option<IpBlockInfo>,
+    /// Readable summary, e.g. `pods matching app=web in namespaces matching team=a`.
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IpBlockInfo {
+    pub cidr: String,
+    pub except: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReachabilityQuery {
+    /// Source pod as `namespace/name`.
+    pub from: String,
+    /// Destination pod as `namespace/name`.
+    pub to: String,
+    /// Port number or name of a destination container port; any port if omitted.
+    pub port: Option<String>,
+    /// Defaults to `TCP`.
+    pub protocol: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReachabilityReport {
+    pub from: String,
+    pub to: String,
+    pub protocol: String,
+    pub port: Option<i32>,
+    pub allowed: bool,
+    pub summary: String,
+    /// Policies in the source namespace limiting what the source pod may send.
+    pub egress: DirectionVerdict,
+    /// Policies in the destination namespace limiting what the destination pod may receive.
+    pub ingress: DirectionVerdict,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DirectionVerdict {
+    /// Whether any policy selects the pod for this direction; if not, all traffic is allowed.
+    pub isolated: bool,
+    pub allowed: bool,
+    /// Every policy selecting the pod for this direction.
+    pub policies: Vec<PolicyVerdict>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PolicyVerdict {
+    pub policy: String,
+    pub namespace: String,
+    pub allows: bool,
+    /// Index of the first rule that allows the traffic.
+    pub rule: Option<usize>,
+    /// Ports of that rule; empty means all ports.
+    pub ports: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngressInfo {
+    pub name: String,
+    pub namespace: String,
+    pub class_name: Option<String>,
+    pub hosts: Vec<String>,
+    pub paths: Vec<IngressPathInfo>,
+    pub default_backend: Option<RouteBackend>,
+    pub tls: Vec<IngressTlsInfo>,
+    pub addresses: Vec<String>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngressPathInfo {
+    /// `None` matches any host.
+    pub host: Option<String>,
+    pub path: String,
+    pub path_type: String,
+    pub backend: RouteBackend,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngressTlsInfo {
+    pub hosts: Vec<String>,
+    pub secret_name: Option<String>,
+}
+
+/// Where an Ingress path or HTTPRoute rule sends traffic.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteBackend {
+    pub kind: String,
+    pub name: String,
+    pub namespace: String,
+    pub port: Option<String>,
+    pub weight: Option<i32>,
+    /// Whether the target Service exists; `None` when it wasn't checked,
+    /// e.g. for non-Service backends or ones in another namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_exists: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HttpRouteInfo {
+    pub name: String,
+    pub namespace: String,
+    pub version: String,
+    /// Gateways the route attaches to, as `[namespace/]name[:section]`.
+    pub parents: Vec<String>,
+    pub hostnames: Vec<String>,
+    pub rules: Vec<HttpRouteRuleInfo>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HttpRouteRuleInfo {
+    pub matches: Vec<String>,
+    pub backends: Vec<RouteBackend>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContainerInfo {
     pub name: String,
     pub image: String,
     pub ports: Vec<String>,
     pub resources: String,
+    pub requests: ResourceAmounts,
+    pub limits: ResourceAmounts,
+    pub usage: Option<ResourceAmounts>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -81,9 +493,13 @@ pub struct PodDetails {
     pub labels: BTreeMap<String, String>,
     pub annotations: BTreeMap<String, String>,
     pub node_name: String,
+    pub host_ip: Option<String>,
+    pub pod_ip: Option<String>,
     pub status: String,
     pub containers: Vec<ContainerInfo>,
     pub created_at: Option<String>,
+    /// Total live usage; `None` without metrics-server.
+    pub usage: Option<ResourceAmounts>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -91,4 +507,352 @@ pub struct PodLogs {
     pub pod_name: String,
     pub namespace: String,
     pub logs: String,
-}
\ No newline at end of file
+}
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogQuery {
+    pub container: Option<String>,
+    pub tail_lines: Option<i64>,
+    pub since_seconds: Option<i64>,
+    #[serde(default)]
+    pub timestamps: bool,
+    #[serde(default)]
+    pub previous: bool,
+    pub follow: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExecQuery {
+    pub container: Option<String>,
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScaleRequest {
+    pub replicas: i32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RollbackRequest {
+    pub revision: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePodQuery {
+    pub grace_period_seconds: Option<u32>,
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PodActionResult {
+    pub name: String,
+    pub namespace: String,
+    pub action: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Identity {
+    pub username: String,
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthInfo {
+    pub enabled: bool,
+    pub methods: Vec<String>,
+    pub user: Option<Identity>,
+    /// Kubernetes identity the user's requests are impersonated as.
+    pub impersonating: Option<Identity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub namespace: String,
+    pub secret_type: String,
+    pub keys: Vec<SecretKeyInfo>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretKeyInfo {
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevealSecretRequest {
+    pub key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecretValue {
+    pub name: String,
+    pub namespace: String,
+    pub key: String,
+    /// `utf-8` when the value is text, otherwise `base64`.
+    pub encoding: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConfigMapDetails {
+    pub name: String,
+    pub namespace: String,
+    pub resource_version: String,
+    pub data: BTreeMap<String, String>,
+    /// Sizes in bytes of `binaryData` entries, which are not returned.
+    pub binary_data: BTreeMap<String, usize>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigMapUpdate {
+    /// Version the edit was based on; the update is rejected if the object has changed since.
+    pub resource_version: String,
+    pub data: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigMapDataChange {
+    pub key: String,
+    /// `added`, `removed` or `modified`, describing the submitted data relative to the current object.
+    pub change: String,
+    pub submitted: Option<String>,
+    pub current: Option<String>,
+}
+
+/// Body of the 409 returned when a ConfigMap edit is based on a stale version.
+#[derive(Debug, Serialize)]
+pub struct ConfigMapConflict {
+    pub code: u16,
+    pub reason: String,
+    pub message: String,
+    pub current: ConfigMapDetails,
+    pub diff: Vec<ConfigMapDataChange>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YamlQuery {
+    #[serde(default)]
+    pub managed_fields: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Take ownership of fields managed by someone else instead of failing with a conflict.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApplyResult {
+    pub dry_run: bool,
+    /// The object as returned by the API server, without managedFields.
+    pub yaml: String,
+    /// Unified diff from the live object to the applied one.
+    pub diff: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResourceInfo {
+    /// API group, empty for the core group.
+    pub group: String,
+    pub version: String,
+    pub kind: String,
+    pub plural: String,
+    pub namespaced: bool,
+    pub verbs: Vec<String>,
+    pub short_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrinterColumn {
+    pub name: String,
+    pub column_type: String,
+    pub json_path: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceTable {
+    pub resource: ApiResourceInfo,
+    /// Columns from the CRD's additionalPrinterColumns; empty for built-in kinds.
+    pub columns: Vec<PrinterColumn>,
+    pub rows: Vec<ResourceRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResourceRow {
+    pub name: String,
+    pub namespace: Option<String>,
+    pub created_at: Option<String>,
+    pub cells: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ResourceQuery {
+    /// `yaml` to get the object as YAML instead of JSON.
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EventInfo {
+    pub name: String,
+    pub namespace: String,
+    pub reason: String,
+    pub message: String,
+    /// `Normal` or `Warning`.
+    pub event_type: String,
+    pub count: i32,
+    pub first_timestamp: Option<String>,
+    pub last_timestamp: Option<String>,
+    pub source: String,
+    pub involved_object: InvolvedObject,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvolvedObject {
+    pub kind: String,
+    pub name: String,
+    pub namespace: Option<String>,
+    pub field_path: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EventQuery {
+    /// Kind of the involved object, e.g. `Pod`.
+    pub kind: Option<String>,
+    /// Name of the involved object.
+    pub name: Option<String>,
+    /// Event type, e.g. `Warning`.
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeInfo {
+    pub name: String,
+    pub roles: Vec<String>,
+    /// `Ready`, `NotReady` or `Unknown`, from the Ready condition.
+    pub status: String,
+    pub unschedulable: bool,
+    pub kubelet_version: String,
+    pub os: String,
+    pub arch: String,
+    pub internal_ip: Option<String>,
+    pub conditions: Vec<ConditionInfo>,
+    pub taints: Vec<TaintInfo>,
+    pub capacity: BTreeMap<String, String>,
+    pub allocatable: BTreeMap<String, String>,
+    /// Pods scheduled to the node. Only filled in by the list and detail endpoints,
+    /// not by watch events, and `None` when pods can't be listed.
+    pub pod_count: Option<usize>,
+    /// Live usage from metrics.k8s.io, filled in like `pod_count`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ResourceAmounts>,
+    pub created_at: Option<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConditionInfo {
+    pub condition_type: String,
+    pub status: String,
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub last_transition_time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaintInfo {
+    pub key: String,
+    pub value: Option<String>,
+    pub effect: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeDetails {
+    #[serde(flatten)]
+    pub node: NodeInfo,
+    pub annotations: BTreeMap<String, String>,
+    pub addresses: Vec<NodeAddressInfo>,
+    pub os_image: String,
+    pub kernel_version: String,
+    pub container_runtime: String,
+    pub pod_cidrs: Vec<String>,
+    pub pods: Vec<PodInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NodeAddressInfo {
+    pub address_type: String,
+    pub address: String,
+}
+
+/// Uses the API's camelCase field names, like `DeletePodQuery`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrainRequest {
+    /// Overrides the configured drain timeout.
+    pub timeout_seconds: Option<u64>,
+    /// Grace period passed to each eviction; the pod's own setting when unset.
+    pub grace_period_seconds: Option<u32>,
+}
+
+/// Progress of a drain, streamed to the client as server-sent events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DrainEvent {
+    Cordoned { node: String },
+    /// DaemonSet-managed and mirror pods are left on the node, as `kubectl drain` does.
+    Skipped { namespace: String, name: String, reason: String },
+    Evicting { namespace: String, name: String },
+    /// The eviction was refused by a PodDisruptionBudget and is being retried.
+    Blocked { namespace: String, name: String, message: String },
+    Evicted { namespace: String, name: String },
+    Failed { namespace: String, name: String, message: String },
+    Done { evicted: usize, skipped: usize, failed: usize },
+    Error { message: String },
+}
+
+/// CPU and memory in base units, for requests, limits and usage alike.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourceAmounts {
+    pub cpu_millis: Option<f64>,
+    pub memory_bytes: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSample {
+    pub timestamp: String,
+    pub cpu_millis: Option<f64>,
+    pub memory_bytes: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsHistoryResponse {
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub interval_seconds: u64,
+    pub retention_seconds: u64,
+    /// Oldest first.
+    pub samples: Vec<MetricsSample>,
+}
@@ -0,0 +1,674 @@
+// src/netpol.rs
+use k8s_openapi::api::core::v1::{Namespace, Pod};
+use k8s_openapi::api::networking::v1::{IPBlock, NetworkPolicy, NetworkPolicyPeer, NetworkPolicyPort};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::{Api, Client, ResourceExt};
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use warp::Rejection;
+
+use crate::error::{reject, ApiError};
+use crate::handlers::network_policy_info;
+use crate::k8s_client::format_selector;
+use crate::models::{
+    DirectionVerdict, IpBlockInfo, NetworkPolicyDetails, NetworkPolicyPeerInfo, NetworkPolicyRuleInfo, PolicyVerdict,
+    ReachabilityQuery, ReachabilityReport,
+};
+
+/// Label the API server sets on every namespace, relied on by namespace selectors.
+const NAMESPACE_NAME_LABEL: &str = "kubernetes.io/metadata.name";
+
+pub async fn get_network_policy(namespace: String, name: String, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let policies: Api<NetworkPolicy> = Api::namespaced(client, &namespace);
+    let np = policies.get(&name).await.map_err(reject)?;
+    let spec = np.spec.as_ref();
+
+    let details = NetworkPolicyDetails {
+        policy: network_policy_info(&np),
+        annotations: np.annotations().clone(),
+        ingress: spec
+            .and_then(|s| s.ingress.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|rule| rule_info(rule.from.as_deref(), rule.ports.as_deref()))
+            .collect(),
+        egress: spec
+            .and_then(|s| s.egress.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|rule| rule_info(rule.to.as_deref(), rule.ports.as_deref()))
+            .collect(),
+    };
+
+    Ok(warp::reply::json(&details))
+}
+
+/// Policy types in effect. Without an explicit list, Ingress always applies and
+/// Egress applies only if the policy has egress rules.
+pub fn policy_types(np: &NetworkPolicy) -> Vec<String> {
+    let spec = np.spec.as_ref();
+    if let Some(types) = spec.and_then(|s| s.policy_types.clone()) {
+        return types;
+    }
+    let mut types = vec!["Ingress".to_string()];
+    if spec.is_some_and(|s| s.egress.is_some()) {
+        types.push("Egress".to_string());
+    }
+    types
+}
+
+fn rule_info(peers: Option<&[NetworkPolicyPeer]>, ports: Option<&[NetworkPolicyPort]>) -> NetworkPolicyRuleInfo {
+    NetworkPolicyRuleInfo {
+        peers: peers.unwrap_or_default().iter().map(peer_info).collect(),
+        ports: ports.unwrap_or_default().iter().map(format_port).collect(),
+    }
+}
+
+fn peer_info(peer: &NetworkPolicyPeer) -> NetworkPolicyPeerInfo {
+    NetworkPolicyPeerInfo {
+        pod_selector: peer.pod_selector.as_ref().map(format_selector),
+        namespace_selector: peer.namespace_selector.as_ref().map(format_selector),
+        ip_block: peer.ip_block.as_ref().map(|block| IpBlockInfo {
+            cidr: block.cidr.clone(),
+            except: block.except.clone().unwrap_or_default(),
+        }),
+        description: describe_peer(peer),
+    }
+}
+
+fn describe_peer(peer: &NetworkPolicyPeer) -> String {
+    let selector = |s: &LabelSelector| {
+        let formatted = format_selector(s);
+        if formatted.is_empty() { None } else { Some(formatted) }
+    };
+    if let Some(block) = &peer.ip_block {
+        return match block.except.as_deref() {
+            Some(except) if !except.is_empty() => format!("{} except {}", block.cidr, except.join(", ")),
+            _ => block.cidr.clone(),
+        };
+    }
+    let pods = match peer.pod_selector.as_ref().map(selector) {
+        Some(Some(s)) => format!("pods matching {}", s),
+        _ => "all pods".to_string(),
+    };
+    match peer.namespace_selector.as_ref().map(selector) {
+        None => format!("{} in the policy's namespace", pods),
+        Some(None) => format!("{} in all namespaces", pods),
+        Some(Some(s)) => format!("{} in namespaces matching {}", pods, s),
+    }
+}
+
+/// Renders a port as e.g. `TCP/80`, `TCP/8000-8080`, `UDP/dns` or `TCP/any`.
+fn format_port(port: &NetworkPolicyPort) -> String {
+    let protocol = port.protocol.as_deref().unwrap_or("TCP");
+    match (&port.port, port.end_port) {
+        (Some(IntOrString::Int(start)), Some(end)) => format!("{}/{}-{}", protocol, start, end),
+        (Some(IntOrString::Int(port)), None) => format!("{}/{}", protocol, port),
+        (Some(IntOrString::String(name)), _) => format!("{}/{}", protocol, name),
+        (None, _) => format!("{}/any", protocol),
+    }
+}
+
+/// Checks whether the source pod may open a connection to the destination pod,
+/// given every NetworkPolicy in both namespaces.
+pub async fn check_reachability(query: ReachabilityQuery, client: Client) -> Result<impl warp::Reply, Rejection> {
+    let (source_ns, source_name) = split_pod_ref(&query.from)?;
+    let (dest_ns, dest_name) = split_pod_ref(&query.to)?;
+    let protocol = query.protocol.clone().unwrap_or_else(|| "TCP".to_string()).to_uppercase();
+
+    let source = Api::<Pod>::namespaced(client.clone(), source_ns).get(source_name).await.map_err(reject)?;
+    let destination = Api::<Pod>::namespaced(client.clone(), dest_ns).get(dest_name).await.map_err(reject)?;
+
+    let port = match query.port.as_deref() {
+        None | Some("") => None,
+        Some(port) => match port.parse::<i32>() {
+            Ok(number) => Some(number),
+            Err(_) => Some(named_port(&destination, port, &protocol).ok_or_else(|| {
+                warp::reject::custom(ApiError::bad_request(format!(
+                    "{} has no {} port named {}",
+                    query.to, protocol, port
+                )))
+            })?),
+        },
+    };
+
+    let source_ns_labels = namespace_labels(&client, source_ns).await?;
+    let dest_ns_labels = namespace_labels(&client, dest_ns).await?;
+    let source_policies = Api::<NetworkPolicy>::namespaced(client.clone(), source_ns)
+        .list(&Default::default())
+        .await
+        .map_err(reject)?
+        .items;
+    let dest_policies = if dest_ns == source_ns {
+        source_policies.clone()
+    } else {
+        Api::<NetworkPolicy>::namespaced(client, dest_ns)
+            .list(&Default::default())
+            .await
+            .map_err(reject)?
+            .items
+    };
+
+    let traffic = Traffic {
+        source: Endpoint { pod: &source, namespace_labels: &source_ns_labels },
+        destination: Endpoint { pod: &destination, namespace_labels: &dest_ns_labels },
+        protocol: &protocol,
+        port,
+    };
+    let egress = evaluate(&source_policies, &traffic, Direction::Egress);
+    let ingress = evaluate(&dest_policies, &traffic, Direction::Ingress);
+
+    let report = ReachabilityReport {
+        from: query.from.clone(),
+        to: query.to.clone(),
+        protocol,
+        port,
+        allowed: egress.allowed && ingress.allowed,
+        summary: summarize(&query, &egress, &ingress),
+        egress,
+        ingress,
+    };
+
+    Ok(warp::reply::json(&report))
+}
+
+fn split_pod_ref(value: &str) -> Result<(&str, &str), Rejection> {
+    match value.split_once('/') {
+        Some((ns, name)) if !ns.is_empty() && !name.is_empty() => Ok((ns, name)),
+        _ => Err(warp::reject::custom(ApiError::bad_request(format!(
+            "expected a pod as namespace/name, got {:?}",
+            value
+        )))),
+    }
+}
+
+async fn namespace_labels(client: &Client, name: &str) -> Result<BTreeMap<String, String>, Rejection> {
+    let namespace = Api::<Namespace>::all(client.clone()).get(name).await.map_err(reject)?;
+    let mut labels = namespace.labels().clone();
+    labels
+        .entry(NAMESPACE_NAME_LABEL.to_string())
+        .or_insert_with(|| name.to_string());
+    Ok(labels)
+}
+
+fn summarize(query: &ReachabilityQuery, egress: &DirectionVerdict, ingress: &DirectionVerdict) -> String {
+    let blocked_by = |verdict: &DirectionVerdict| {
+        verdict.policies.iter().map(|p| p.policy.as_str()).collect::<Vec<_>>().join(", ")
+    };
+    match (egress.allowed, ingress.allowed) {
+        (true, true) => format!("{} can reach {}", query.from, query.to),
+        (false, true) => format!("Blocked by egress from {}: {}", query.from, blocked_by(egress)),
+        (true, false) => format!("Blocked by ingress to {}: {}", query.to, blocked_by(ingress)),
+        (false, false) => format!(
+            "Blocked by egress from {} ({}) and ingress to {} ({})",
+            query.from,
+            blocked_by(egress),
+            query.to,
+            blocked_by(ingress)
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Ingress,
+    Egress,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Ingress => "Ingress",
+            Direction::Egress => "Egress",
+        }
+    }
+}
+
+/// Peers and ports of an ingress or egress rule; `None` means any.
+type Rule<'a> = (Option<&'a [NetworkPolicyPeer]>, Option<&'a [NetworkPolicyPort]>);
+
+/// A pod together with the labels of its namespace, as seen by policy selectors.
+pub struct Endpoint<'a> {
+    pub pod: &'a Pod,
+    pub namespace_labels: &'a BTreeMap<String, String>,
+}
+
+/// A single connection attempt. `port` is `None` when any port will do.
+pub struct Traffic<'a> {
+    pub source: Endpoint<'a>,
+    pub destination: Endpoint<'a>,
+    pub protocol: &'a str,
+    pub port: Option<i32>,
+}
+
+/// Evaluates the policies of one side of a connection: egress policies in the
+/// source namespace or ingress policies in the destination namespace. Traffic is
+/// allowed if no policy selects the pod for that direction, or if any rule of a
+/// selecting policy matches the peer and the port.
+pub fn evaluate(policies: &[NetworkPolicy], traffic: &Traffic, direction: Direction) -> DirectionVerdict {
+    let (subject, peer) = match direction {
+        Direction::Egress => (&traffic.source, &traffic.destination),
+        Direction::Ingress => (&traffic.destination, &traffic.source),
+    };
+    let subject_ns = subject.pod.namespace().unwrap_or_default();
+    let subject_labels = subject.pod.labels();
+
+    let verdicts: Vec<PolicyVerdict> = policies
+        .iter()
+        .filter(|np| np.namespace().unwrap_or_default() == subject_ns)
+        .filter(|np| policy_types(np).iter().any(|t| t == direction.as_str()))
+        .filter(|np| np.spec.as_ref().is_some_and(|s| selector_matches(&s.pod_selector, subject_labels)))
+        .map(|np| {
+            let rules: Vec<Rule> = match direction {
+                Direction::Ingress => np
+                    .spec
+                    .as_ref()
+                    .and_then(|s| s.ingress.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .map(|r| (r.from.as_deref(), r.ports.as_deref()))
+                    .collect(),
+                Direction::Egress => np
+                    .spec
+                    .as_ref()
+                    .and_then(|s| s.egress.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .map(|r| (r.to.as_deref(), r.ports.as_deref()))
+                    .collect(),
+            };
+            let policy_ns = np.namespace().unwrap_or_default();
+            let matching = rules.iter().position(|(peers, ports)| {
+                let peer_ok = match peers {
+                    None | Some([]) => true,
+                    Some(peers) => peers.iter().any(|p| peer_matches(p, &policy_ns, peer)),
+                };
+                peer_ok && ports_match(*ports, traffic)
+            });
+            PolicyVerdict {
+                policy: np.name_any(),
+                namespace: policy_ns,
+                allows: matching.is_some(),
+                rule: matching,
+                ports: matching
+                    .and_then(|i| rules[i].1)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(format_port)
+                    .collect(),
+            }
+        })
+        .collect();
+
+    DirectionVerdict {
+        isolated: !verdicts.is_empty(),
+        allowed: verdicts.is_empty() || verdicts.iter().any(|v| v.allows),
+        policies: verdicts,
+    }
+}
+
+fn peer_matches(peer: &NetworkPolicyPeer, policy_ns: &str, other: &Endpoint) -> bool {
+    if let Some(block) = &peer.ip_block {
+        return pod_ips(other.pod).iter().any(|ip| ip_block_contains(block, ip));
+    }
+    let pod_ok = peer
+        .pod_selector
+        .as_ref()
+        .is_none_or(|s| selector_matches(s, other.pod.labels()));
+    let namespace_ok = match &peer.namespace_selector {
+        Some(selector) => selector_matches(selector, other.namespace_labels),
+        None => other.pod.namespace().as_deref() == Some(policy_ns),
+    };
+    // A peer with neither selector is invalid and matches nothing
+    (peer.pod_selector.is_some() || peer.namespace_selector.is_some()) && pod_ok && namespace_ok
+}
+
+fn ports_match(ports: Option<&[NetworkPolicyPort]>, traffic: &Traffic) -> bool {
+    let ports = match ports {
+        None | Some([]) => return true,
+        Some(ports) => ports,
+    };
+    ports.iter().any(|p| {
+        if p.protocol.as_deref().unwrap_or("TCP") != traffic.protocol {
+            return false;
+        }
+        let Some(port) = traffic.port else { return true };
+        match &p.port {
+            None => true,
+            Some(IntOrString::Int(start)) => match p.end_port {
+                Some(end) => (*start..=end).contains(&port),
+                None => *start == port,
+            },
+            // Named ports always refer to the destination pod's container ports
+            Some(IntOrString::String(name)) => named_port(traffic.destination.pod, name, traffic.protocol) == Some(port),
+        }
+    })
+}
+
+fn named_port(pod: &Pod, name: &str, protocol: &str) -> Option<i32> {
+    pod.spec
+        .as_ref()?
+        .containers
+        .iter()
+        .flat_map(|c| c.ports.iter().flatten())
+        .find(|p| p.name.as_deref() == Some(name) && p.protocol.as_deref().unwrap_or("TCP") == protocol)
+        .map(|p| p.container_port)
+}
+
+fn pod_ips(pod: &Pod) -> Vec<String> {
+    let status = pod.status.as_ref();
+    let ips: Vec<String> = status
+        .and_then(|s| s.pod_ips.as_ref())
+        .map(|ips| ips.iter().filter_map(|ip| ip.ip.clone()).collect())
+        .unwrap_or_default();
+    if ips.is_empty() {
+        status.and_then(|s| s.pod_ip.clone()).into_iter().collect()
+    } else {
+        ips
+    }
+}
+
+/// Matches labels against a selector, including `matchExpressions`. An empty selector matches everything.
+pub fn selector_matches(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    let labels_ok = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .all(|(k, v)| labels.get(k) == Some(v));
+    let expressions_ok = selector.match_expressions.iter().flatten().all(|expr| {
+        let values = expr.values.as_deref().unwrap_or_default();
+        let value = labels.get(&expr.key);
+        match expr.operator.as_str() {
+            "In" => value.is_some_and(|v| values.contains(v)),
+            "NotIn" => value.is_none_or(|v| !values.contains(v)),
+            "Exists" => value.is_some(),
+            "DoesNotExist" => value.is_none(),
+            _ => false,
+        }
+    });
+    labels_ok && expressions_ok
+}
+
+fn ip_block_contains(block: &IPBlock, ip: &str) -> bool {
+    cidr_contains(&block.cidr, ip) && !block.except.iter().flatten().any(|except| cidr_contains(except, ip))
+}
+
+fn cidr_contains(cidr: &str, ip: &str) -> bool {
+    let (Some((network, prefix)), Ok(ip)) = (cidr.split_once('/'), ip.parse::<IpAddr>()) else {
+        return false;
+    };
+    let (Ok(network), Ok(prefix)) = (network.parse::<IpAddr>(), prefix.parse::<u32>()) else {
+        return false;
+    };
+    let (network, ip, bits) = match (network, ip) {
+        (IpAddr::V4(n), IpAddr::V4(i)) => (u32::from(n) as u128, u32::from(i) as u128, 32),
+        (IpAddr::V6(n), IpAddr::V6(i)) => (u128::from(n), u128::from(i), 128),
+        _ => return false,
+    };
+    if prefix > bits {
+        return false;
+    }
+    let shift = bits - prefix;
+    shift == bits || (network >> shift) == (ip >> shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pod(namespace: &str, name: &str, app: &str, ip: &str) -> Pod {
+        serde_json::from_value(json!({
+            "metadata": { "name": name, "namespace": namespace, "labels": { "app": app } },
+            "spec": { "containers": [{
+                "name": "main",
+                "image": "img",
+                "ports": [{ "name": "http", "containerPort": 8080 }, { "name": "dns", "containerPort": 53, "protocol": "UDP" }]
+            }] },
+            "status": { "podIP": ip, "podIPs": [{ "ip": ip }] }
+        }))
+        .unwrap()
+    }
+
+    fn policy(namespace: &str, name: &str, spec: serde_json::Value) -> NetworkPolicy {
+        serde_json::from_value(json!({ "metadata": { "name": name, "namespace": namespace }, "spec": spec })).unwrap()
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn selector(value: serde_json::Value) -> LabelSelector {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ip_block(cidr: &str, except: &[&str]) -> IPBlock {
+        IPBlock { cidr: cidr.to_string(), except: Some(except.iter().map(|e| e.to_string()).collect()) }
+    }
+
+    struct Fixture {
+        web: Pod,
+        db: Pod,
+        client: Pod,
+        default_labels: BTreeMap<String, String>,
+        prod_labels: BTreeMap<String, String>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Fixture {
+                web: pod("default", "web", "web", "10.1.0.1"),
+                db: pod("default", "db", "db", "10.1.0.2"),
+                client: pod("prod", "client", "client", "10.2.0.1"),
+                default_labels: labels(&[(NAMESPACE_NAME_LABEL, "default")]),
+                prod_labels: labels(&[(NAMESPACE_NAME_LABEL, "prod"), ("team", "a")]),
+            }
+        }
+
+        fn endpoint<'a>(&'a self, pod: &'a Pod) -> Endpoint<'a> {
+            let namespace_labels = if pod.namespace().as_deref() == Some("prod") {
+                &self.prod_labels
+            } else {
+                &self.default_labels
+            };
+            Endpoint { pod, namespace_labels }
+        }
+
+        fn traffic<'a>(&'a self, source: &'a Pod, destination: &'a Pod, port: Option<i32>) -> Traffic<'a> {
+            Traffic {
+                source: self.endpoint(source),
+                destination: self.endpoint(destination),
+                protocol: "TCP",
+                port,
+            }
+        }
+    }
+
+    #[test]
+    fn selector_operators_with_key_missing_and_present() {
+        let present = labels(&[("tier", "web")]);
+        let other = labels(&[("tier", "db")]);
+        let missing = labels(&[]);
+
+        let in_web = selector(json!({ "matchExpressions": [{ "key": "tier", "operator": "In", "values": ["web"] }] }));
+        assert!(selector_matches(&in_web, &present));
+        assert!(!selector_matches(&in_web, &other));
+        assert!(!selector_matches(&in_web, &missing));
+
+        let not_in_web = selector(json!({ "matchExpressions": [{ "key": "tier", "operator": "NotIn", "values": ["web"] }] }));
+        assert!(!selector_matches(&not_in_web, &present));
+        assert!(selector_matches(&not_in_web, &other));
+        assert!(selector_matches(&not_in_web, &missing));
+
+        let exists = selector(json!({ "matchExpressions": [{ "key": "tier", "operator": "Exists" }] }));
+        assert!(selector_matches(&exists, &present));
+        assert!(!selector_matches(&exists, &missing));
+
+        let absent = selector(json!({ "matchExpressions": [{ "key": "tier", "operator": "DoesNotExist" }] }));
+        assert!(!selector_matches(&absent, &present));
+        assert!(selector_matches(&absent, &missing));
+    }
+
+    #[test]
+    fn empty_selector_matches_everything() {
+        assert!(selector_matches(&LabelSelector::default(), &labels(&[])));
+        assert!(selector_matches(&LabelSelector::default(), &labels(&[("app", "web")])));
+    }
+
+    #[test]
+    fn cidr_prefix_lengths() {
+        assert!(cidr_contains("0.0.0.0/0", "203.0.113.9"));
+        assert!(cidr_contains("10.1.2.3/32", "10.1.2.3"));
+        assert!(!cidr_contains("10.1.2.3/32", "10.1.2.4"));
+        assert!(cidr_contains("10.0.0.0/8", "10.255.0.1"));
+        assert!(!cidr_contains("10.0.0.0/8", "11.0.0.1"));
+    }
+
+    #[test]
+    fn cidr_ipv6_and_mixed_families() {
+        assert!(cidr_contains("fd00::/8", "fd12:3456::1"));
+        assert!(!cidr_contains("fd00::/8", "fe80::1"));
+        assert!(cidr_contains("::/0", "2001:db8::1"));
+        assert!(!cidr_contains("fd00::/8", "10.0.0.1"));
+        assert!(!cidr_contains("10.0.0.0/8", "fd00::1"));
+        assert!(!cidr_contains("10.0.0.0/33", "10.0.0.1"));
+    }
+
+    #[test]
+    fn ip_block_except() {
+        let block = ip_block("10.2.0.0/16", &["10.2.0.0/24"]);
+        assert!(ip_block_contains(&block, "10.2.5.5"));
+        assert!(!ip_block_contains(&block, "10.2.0.1"));
+        assert!(!ip_block_contains(&block, "10.3.0.1"));
+    }
+
+    #[test]
+    fn policy_types_default_from_rules() {
+        let ingress_only = policy("default", "a", json!({ "podSelector": {}, "ingress": [{}] }));
+        assert_eq!(policy_types(&ingress_only), vec!["Ingress"]);
+
+        let with_egress = policy("default", "b", json!({ "podSelector": {}, "egress": [{}] }));
+        assert_eq!(policy_types(&with_egress), vec!["Ingress", "Egress"]);
+
+        let explicit = policy("default", "c", json!({ "podSelector": {}, "policyTypes": ["Egress"] }));
+        assert_eq!(policy_types(&explicit), vec!["Egress"]);
+    }
+
+    #[test]
+    fn unselected_pod_is_not_isolated() {
+        let f = Fixture::new();
+        let policies = [policy("default", "db-only", json!({ "podSelector": { "matchLabels": { "app": "db" } } }))];
+        let verdict = evaluate(&policies, &f.traffic(&f.db, &f.web, Some(8080)), Direction::Ingress);
+        assert!(!verdict.isolated);
+        assert!(verdict.allowed);
+        assert!(verdict.policies.is_empty());
+    }
+
+    #[test]
+    fn default_deny_blocks_ingress() {
+        let f = Fixture::new();
+        let policies = [policy("default", "deny-all", json!({ "podSelector": {}, "policyTypes": ["Ingress"] }))];
+        let verdict = evaluate(&policies, &f.traffic(&f.db, &f.web, Some(8080)), Direction::Ingress);
+        assert!(verdict.isolated);
+        assert!(!verdict.allowed);
+        assert_eq!(verdict.policies[0].policy, "deny-all");
+        assert!(!verdict.policies[0].allows);
+    }
+
+    #[test]
+    fn namespace_selector_peer() {
+        let f = Fixture::new();
+        let policies = [policy("default", "from-team-a", json!({
+            "podSelector": { "matchLabels": { "app": "web" } },
+            "ingress": [{ "from": [{ "namespaceSelector": { "matchLabels": { "team": "a" } } }] }]
+        }))];
+        let allowed = evaluate(&policies, &f.traffic(&f.client, &f.web, Some(8080)), Direction::Ingress);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.policies[0].rule, Some(0));
+
+        let blocked = evaluate(&policies, &f.traffic(&f.db, &f.web, Some(8080)), Direction::Ingress);
+        assert!(!blocked.allowed);
+    }
+
+    #[test]
+    fn pod_selector_peer_is_limited_to_policy_namespace() {
+        let f = Fixture::new();
+        let prod_web = pod("prod", "web", "web", "10.2.0.9");
+        let policies = [policy("default", "from-web", json!({
+            "podSelector": { "matchLabels": { "app": "db" } },
+            "ingress": [{ "from": [{ "podSelector": { "matchLabels": { "app": "web" } } }] }]
+        }))];
+        assert!(evaluate(&policies, &f.traffic(&f.web, &f.db, None), Direction::Ingress).allowed);
+        assert!(!evaluate(&policies, &f.traffic(&prod_web, &f.db, None), Direction::Ingress).allowed);
+    }
+
+    #[test]
+    fn named_port_resolves_on_destination() {
+        let f = Fixture::new();
+        let policies = [policy("default", "http-only", json!({
+            "podSelector": { "matchLabels": { "app": "web" } },
+            "ingress": [{ "ports": [{ "port": "http" }] }]
+        }))];
+        assert!(evaluate(&policies, &f.traffic(&f.db, &f.web, Some(8080)), Direction::Ingress).allowed);
+        assert!(!evaluate(&policies, &f.traffic(&f.db, &f.web, Some(80)), Direction::Ingress).allowed);
+
+        // The name is looked up with the traffic's protocol, so a UDP port of that name doesn't count
+        let dns = [policy("default", "dns", json!({
+            "podSelector": { "matchLabels": { "app": "web" } },
+            "ingress": [{ "ports": [{ "port": "dns" }] }]
+        }))];
+        assert!(!evaluate(&dns, &f.traffic(&f.db, &f.web, Some(53)), Direction::Ingress).allowed);
+    }
+
+    #[test]
+    fn end_port_range() {
+        let f = Fixture::new();
+        let policies = [policy("default", "range", json!({
+            "podSelector": { "matchLabels": { "app": "web" } },
+            "ingress": [{ "ports": [{ "port": 8000, "endPort": 8100 }] }]
+        }))];
+        for (port, allowed) in [(7999, false), (8000, true), (8080, true), (8100, true), (8101, false)] {
+            let verdict = evaluate(&policies, &f.traffic(&f.db, &f.web, Some(port)), Direction::Ingress);
+            assert_eq!(verdict.allowed, allowed, "port {}", port);
+        }
+    }
+
+    #[test]
+    fn egress_blocks_while_ingress_allows() {
+        let f = Fixture::new();
+        let policies = [policy("default", "web-egress", json!({
+            "podSelector": { "matchLabels": { "app": "web" } },
+            "policyTypes": ["Egress"],
+            "egress": [{ "to": [{ "ipBlock": { "cidr": "192.168.0.0/16" } }] }]
+        }))];
+        let traffic = f.traffic(&f.web, &f.db, Some(5432));
+        let egress = evaluate(&policies, &traffic, Direction::Egress);
+        let ingress = evaluate(&policies, &traffic, Direction::Ingress);
+        assert!(egress.isolated && !egress.allowed);
+        assert!(!ingress.isolated && ingress.allowed);
+    }
+
+    #[test]
+    fn ingress_blocks_while_egress_allows() {
+        let f = Fixture::new();
+        let policies = [
+            policy("default", "web-egress", json!({
+                "podSelector": { "matchLabels": { "app": "web" } },
+                "policyTypes": ["Egress"],
+                "egress": [{ "to": [{ "podSelector": { "matchLabels": { "app": "db" } } }], "ports": [{ "port": 5432 }] }]
+            })),
+            policy("default", "db-ingress", json!({
+                "podSelector": { "matchLabels": { "app": "db" } },
+                "ingress": [{ "from": [{ "podSelector": { "matchLabels": { "app": "api" } } }] }]
+            })),
+        ];
+        let traffic = f.traffic(&f.web, &f.db, Some(5432));
+        let egress = evaluate(&policies, &traffic, Direction::Egress);
+        let ingress = evaluate(&policies, &traffic, Direction::Ingress);
+        assert!(egress.allowed);
+        assert_eq!(egress.policies[0].ports, vec!["TCP/5432"]);
+        assert!(ingress.isolated && !ingress.allowed);
+        assert_eq!(ingress.policies[0].policy, "db-ingress");
+    }
+}
@@ -0,0 +1,320 @@
+// src/routing.rs
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::api::networking::v1::{Ingress, IngressBackend};
+use kube::api::DynamicObject;
+use kube::{Api, ResourceExt};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use warp::Rejection;
+
+use crate::cache::{cached_json, CacheStatus, ResourceCache, ResourceKind};
+use crate::discovery::api_resource;
+use crate::error::{reject, ApiError};
+use crate::handlers::{from_values, service_info};
+use crate::models::{
+    HttpRouteInfo, HttpRouteRuleInfo, IngressInfo, IngressPathInfo, IngressTlsInfo, RouteBackend, ServiceInfo,
+};
+
+const GATEWAY_GROUP: &str = "gateway.networking.k8s.io";
+/// Served HTTPRoute versions, preferred first.
+const HTTP_ROUTE_VERSIONS: [&str; 2] = ["v1", "v1beta1"];
+
+/// Lists ingresses with each Service backend checked against the namespace's services.
+pub async fn get_ingresses(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let (mut ingresses, status) = match cache.list(ResourceKind::Ingresses, Some(&namespace)) {
+        Some(cached) => (from_values::<IngressInfo>(cached.objects), cached.status),
+        None => {
+            let api: Api<Ingress> = Api::namespaced(cache.client(), &namespace);
+            let list = api.list(&Default::default()).await.map_err(reject)?;
+            (list.items.iter().map(ingress_info).collect(), CacheStatus::Miss)
+        }
+    };
+
+    let services = namespace_services(&cache, &namespace).await?;
+    for ingress in &mut ingresses {
+        let backends = ingress
+            .default_backend
+            .iter_mut()
+            .chain(ingress.paths.iter_mut().map(|p| &mut p.backend));
+        for backend in backends {
+            resolve_backend(backend, &namespace, &services);
+        }
+    }
+
+    Ok(cached_json(&ingresses, status))
+}
+
+/// Lists Gateway API HTTPRoutes, or 404 when the Gateway API CRDs aren't installed.
+pub async fn get_http_routes(namespace: String, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let client = cache.client();
+    let resources = cache.catalog().resources(&client).await.map_err(warp::reject::custom)?;
+    let info = HTTP_ROUTE_VERSIONS
+        .iter()
+        .find_map(|version| {
+            resources
+                .iter()
+                .find(|r| r.group == GATEWAY_GROUP && r.version == *version && r.kind == "HTTPRoute")
+        })
+        .ok_or_else(|| warp::reject::custom(ApiError::not_found("Gateway API HTTPRoutes are not installed")))?;
+
+    let api: Api<DynamicObject> = Api::namespaced_with(client, &namespace, &api_resource(info));
+    let list = api.list(&Default::default()).await.map_err(reject)?;
+    let services = namespace_services(&cache, &namespace).await?;
+
+    let mut routes: Vec<HttpRouteInfo> = list
+        .items
+        .iter()
+        .map(|obj| http_route_info(obj, &info.version))
+        .collect();
+    for route in &mut routes {
+        for backend in route.rules.iter_mut().flat_map(|rule| rule.backends.iter_mut()) {
+            resolve_backend(backend, &namespace, &services);
+        }
+    }
+
+    Ok(warp::reply::json(&routes))
+}
+
+async fn namespace_services(cache: &ResourceCache, namespace: &str) -> Result<Vec<ServiceInfo>, Rejection> {
+    if let Some(cached) = cache.list(ResourceKind::Services, Some(namespace)) {
+        return Ok(from_values(cached.objects));
+    }
+    let api: Api<Service> = Api::namespaced(cache.client(), namespace);
+    let list = api.list(&Default::default()).await.map_err(reject)?;
+    Ok(list.items.iter().map(service_info).collect())
+}
+
+/// Marks whether a Service backend in the route's own namespace exists.
+/// Backends in other namespaces or of other kinds are left unresolved.
+fn resolve_backend(backend: &mut RouteBackend, namespace: &str, services: &[ServiceInfo]) {
+    if backend.kind == "Service" && backend.namespace == namespace {
+        backend.service_exists = Some(services.iter().any(|s| s.name == backend.name));
+    }
+}
+
+pub fn ingress_info(ingress: &Ingress) -> IngressInfo {
+    let namespace = ingress.namespace().unwrap_or_default();
+    let spec = ingress.spec.as_ref();
+
+    let paths = spec
+        .and_then(|s| s.rules.as_ref())
+        .into_iter()
+        .flatten()
+        .flat_map(|rule| {
+            let host = rule.host.clone();
+            let namespace = &namespace;
+            rule.http
+                .iter()
+                .flat_map(|http| http.paths.iter())
+                .map(move |path| IngressPathInfo {
+                    host: host.clone(),
+                    path: path.path.clone().unwrap_or_else(|| "/".to_string()),
+                    path_type: path.path_type.clone(),
+                    backend: ingress_backend(&path.backend, namespace),
+                })
+        })
+        .collect();
+
+    IngressInfo {
+        name: ingress.name_any(),
+        namespace: namespace.clone(),
+        class_name: spec.and_then(|s| s.ingress_class_name.clone()),
+        hosts: spec
+            .and_then(|s| s.rules.as_ref())
+            .map(|rules| rules.iter().map(|r| r.host.clone().unwrap_or_else(|| "*".to_string())).collect())
+            .unwrap_or_default(),
+        paths,
+        default_backend: spec
+            .and_then(|s| s.default_backend.as_ref())
+            .map(|b| ingress_backend(b, &namespace)),
+        tls: spec
+            .and_then(|s| s.tls.as_ref())
+            .map(|tls| {
+                tls.iter()
+                    .map(|t| IngressTlsInfo {
+                        hosts: t.hosts.clone().unwrap_or_default(),
+                        secret_name: t.secret_name.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        addresses: ingress
+            .status
+            .as_ref()
+            .and_then(|s| s.load_balancer.as_ref())
+            .and_then(|lb| lb.ingress.as_ref())
+            .map(|entries| entries.iter().filter_map(|e| e.ip.clone().or_else(|| e.hostname.clone())).collect())
+            .unwrap_or_default(),
+        created_at: ingress.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: ingress.labels().clone(),
+    }
+}
+
+fn ingress_backend(backend: &IngressBackend, namespace: &str) -> RouteBackend {
+    match (&backend.service, &backend.resource) {
+        (Some(service), _) => RouteBackend {
+            kind: "Service".to_string(),
+            name: service.name.clone(),
+            namespace: namespace.to_string(),
+            port: service.port.as_ref().and_then(|p| {
+                p.number.map(|n| n.to_string()).or_else(|| p.name.clone())
+            }),
+            weight: None,
+            service_exists: None,
+        },
+        (None, Some(resource)) => RouteBackend {
+            kind: resource.kind.clone(),
+            name: resource.name.clone(),
+            namespace: namespace.to_string(),
+            port: None,
+            weight: None,
+            service_exists: None,
+        },
+        (None, None) => RouteBackend {
+            kind: String::new(),
+            name: String::new(),
+            namespace: namespace.to_string(),
+            port: None,
+            weight: None,
+            service_exists: None,
+        },
+    }
+}
+
+/// The parts of an HTTPRoute spec shown in the routing view; v1 and v1beta1 share them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpRouteSpec {
+    #[serde(default)]
+    parent_refs: Vec<ParentRef>,
+    #[serde(default)]
+    hostnames: Vec<String>,
+    #[serde(default)]
+    rules: Vec<HttpRouteRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParentRef {
+    name: String,
+    namespace: Option<String>,
+    section_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpRouteRule {
+    #[serde(default)]
+    matches: Vec<HttpRouteMatch>,
+    #[serde(default)]
+    backend_refs: Vec<BackendRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpRouteMatch {
+    path: Option<PathMatch>,
+    method: Option<String>,
+    #[serde(default)]
+    headers: Vec<NameValueMatch>,
+    #[serde(default, rename = "queryParams")]
+    query_params: Vec<NameValueMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PathMatch {
+    #[serde(rename = "type")]
+    match_type: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameValueMatch {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BackendRef {
+    name: String,
+    kind: Option<String>,
+    namespace: Option<String>,
+    port: Option<i32>,
+    weight: Option<i32>,
+}
+
+fn http_route_info(obj: &DynamicObject, version: &str) -> HttpRouteInfo {
+    let namespace = obj.namespace().unwrap_or_default();
+    let spec: HttpRouteSpec = obj
+        .data
+        .get("spec")
+        .and_then(|spec| serde_json::from_value(spec.clone()).ok())
+        .unwrap_or_default();
+
+    HttpRouteInfo {
+        name: obj.name_any(),
+        namespace: namespace.clone(),
+        version: version.to_string(),
+        parents: spec
+            .parent_refs
+            .iter()
+            .map(|p| {
+                let name = match &p.namespace {
+                    Some(ns) if *ns != namespace => format!("{}/{}", ns, p.name),
+                    _ => p.name.clone(),
+                };
+                match &p.section_name {
+                    Some(section) => format!("{}:{}", name, section),
+                    None => name,
+                }
+            })
+            .collect(),
+        hostnames: spec.hostnames,
+        rules: spec
+            .rules
+            .iter()
+            .map(|rule| HttpRouteRuleInfo {
+                matches: if rule.matches.is_empty() {
+                    vec!["PathPrefix /".to_string()]
+                } else {
+                    rule.matches.iter().map(format_match).collect()
+                },
+                backends: rule
+                    .backend_refs
+                    .iter()
+                    .map(|b| RouteBackend {
+                        kind: b.kind.clone().unwrap_or_else(|| "Service".to_string()),
+                        name: b.name.clone(),
+                        namespace: b.namespace.clone().unwrap_or_else(|| namespace.clone()),
+                        port: b.port.map(|p| p.to_string()),
+                        weight: b.weight,
+                        service_exists: None,
+                    })
+                    .collect(),
+            })
+            .collect(),
+        created_at: obj.creation_timestamp().map(|ts| ts.0.to_rfc3339()),
+        labels: obj.labels().clone(),
+    }
+}
+
+/// Renders a match as e.g. `GET PathPrefix /api header:x-env=prod`.
+fn format_match(m: &HttpRouteMatch) -> String {
+    let mut parts = Vec::new();
+    if let Some(method) = &m.method {
+        parts.push(method.clone());
+    }
+    let path = m.path.as_ref();
+    parts.push(format!(
+        "{} {}",
+        path.and_then(|p| p.match_type.as_deref()).unwrap_or("PathPrefix"),
+        path.and_then(|p| p.value.as_deref()).unwrap_or("/")
+    ));
+    let conditions: BTreeMap<String, &str> = m
+        .headers
+        .iter()
+        .map(|h| (format!("header:{}", h.name), h.value.as_str()))
+        .chain(m.query_params.iter().map(|q| (format!("query:{}", q.name), q.value.as_str())))
+        .collect();
+    parts.extend(conditions.into_iter().map(|(name, value)| format!("{}={}", name, value)));
+    parts.join(" ")
+}
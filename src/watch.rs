@@ -0,0 +1,18 @@
+// src/watch.rs
+use futures::StreamExt;
+use warp::Rejection;
+
+use crate::cache::{ResourceCache, ResourceKind};
+use crate::error::ApiError;
+
+pub async fn watch_resources(kind: String, namespace: Option<String>, cache: ResourceCache) -> Result<impl warp::Reply, Rejection> {
+    let kind = ResourceKind::from_path(&kind)
+        .ok_or_else(|| warp::reject::custom(ApiError::not_found(format!("Unknown resource kind: {}", kind))))?;
+    let namespace = if kind.is_namespaced() { namespace } else { None };
+
+    let events = cache
+        .subscribe(kind, namespace)
+        .map(|event| warp::sse::Event::default().json_data(event));
+
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+}
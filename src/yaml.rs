@@ -0,0 +1,136 @@
+// src/yaml.rs
+use kube::api::{ApiResource, DynamicObject, Patch, PatchParams, TypeMeta};
+use kube::{Api, Client, ResourceExt};
+use similar::TextDiff;
+use warp::http::header::CONTENT_TYPE;
+use warp::hyper::body::Bytes;
+use warp::{Rejection, Reply};
+
+use crate::cache::ResourceKind;
+use crate::error::{reject, ApiError};
+use crate::handlers::FIELD_MANAGER;
+use crate::models::{ApplyQuery, ApplyResult, YamlQuery};
+
+/// Returns the full object as YAML, without managedFields unless asked for.
+pub async fn get_resource_yaml(
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+    query: YamlQuery,
+    client: Client,
+) -> Result<impl Reply, Rejection> {
+    let (api, _) = dynamic_api(&kind, namespace.as_deref(), client)?;
+
+    let mut obj = api.get(&name).await.map_err(reject)?;
+    if !query.managed_fields {
+        obj.metadata.managed_fields = None;
+    }
+
+    Ok(warp::reply::with_header(to_yaml(&obj)?, CONTENT_TYPE, "application/yaml"))
+}
+
+/// Server-side applies an edited YAML document as the `kube-inspector` field manager.
+/// With `dryRun` nothing is persisted, and the diff shows what would change.
+pub async fn apply_resource_yaml(
+    kind: String,
+    namespace: Option<String>,
+    name: String,
+    query: ApplyQuery,
+    body: Bytes,
+    client: Client,
+) -> Result<impl Reply, Rejection> {
+    let (api, resource) = dynamic_api(&kind, namespace.as_deref(), client)?;
+
+    let mut obj: DynamicObject = serde_yaml::from_slice(&body)
+        .map_err(|e| warp::reject::custom(ApiError::bad_request(format!("Invalid YAML: {}", e))))?;
+    match &obj.types {
+        Some(types) if types.kind != resource.kind || types.api_version != resource.api_version => {
+            return Err(warp::reject::custom(ApiError::bad_request(format!(
+                "Expected {} {}, got {} {}",
+                resource.api_version, resource.kind, types.api_version, types.kind
+            ))));
+        }
+        Some(_) => {}
+        // Server-side apply needs apiVersion and kind, so fill them in from the path
+        None => {
+            obj.types = Some(TypeMeta {
+                api_version: resource.api_version.clone(),
+                kind: resource.kind.clone(),
+            })
+        }
+    }
+    if obj.metadata.name.as_deref() != Some(name.as_str()) {
+        return Err(warp::reject::custom(ApiError::bad_request(format!(
+            "metadata.name must be {}",
+            name
+        ))));
+    }
+    if obj.metadata.namespace.is_some() && obj.metadata.namespace != namespace {
+        return Err(warp::reject::custom(ApiError::bad_request(
+            "metadata.namespace does not match the request path",
+        )));
+    }
+    // Apply requests must not carry managedFields; a resourceVersion, if present,
+    // is kept and acts as a precondition.
+    obj.metadata.managed_fields = None;
+
+    let mut params = PatchParams::apply(FIELD_MANAGER);
+    if query.force {
+        params = params.force();
+    }
+    if query.dry_run {
+        params = params.dry_run();
+    }
+
+    let live = api.get_opt(&name).await.map_err(reject)?;
+    let mut applied = api.patch(&name, &params, &Patch::Apply(&obj)).await.map_err(reject)?;
+    applied.metadata.managed_fields = None;
+
+    let before = match live {
+        Some(mut live) => {
+            live.metadata.managed_fields = None;
+            to_yaml(&live)?
+        }
+        None => String::new(),
+    };
+    let after = to_yaml(&applied)?;
+    let diff = TextDiff::from_lines(&before, &after)
+        .unified_diff()
+        .context_radius(3)
+        .header("live", if query.dry_run { "dry-run" } else { "applied" })
+        .to_string();
+
+    Ok(warp::reply::json(&ApplyResult {
+        dry_run: query.dry_run,
+        yaml: after,
+        diff,
+    }))
+}
+
+fn dynamic_api(kind: &str, namespace: Option<&str>, client: Client) -> Result<(Api<DynamicObject>, ApiResource), Rejection> {
+    let kind = ResourceKind::from_path(kind)
+        .ok_or_else(|| warp::reject::custom(ApiError::not_found(format!("Unknown resource kind: {}", kind))))?;
+    let resource = kind.api_resource();
+    match (kind.is_namespaced(), namespace) {
+        (true, Some(ns)) => Ok((Api::namespaced_with(client, ns, &resource), resource)),
+        (false, None) => Ok((Api::all_with(client, &resource), resource)),
+        (true, None) => Err(warp::reject::custom(ApiError::bad_request(format!(
+            "{} are namespaced",
+            resource.plural
+        )))),
+        (false, Some(_)) => Err(warp::reject::custom(ApiError::bad_request(format!(
+            "{} are not namespaced",
+            resource.plural
+        )))),
+    }
+}
+
+pub fn to_yaml(obj: &DynamicObject) -> Result<String, Rejection> {
+    serde_yaml::to_string(obj).map_err(|e| {
+        warp::reject::custom(ApiError::internal(format!(
+            "Failed to serialize {}: {}",
+            obj.name_any(),
+            e
+        )))
+    })
+}